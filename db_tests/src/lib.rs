@@ -0,0 +1,75 @@
+//! Database round trip tests for `sqlx` and `diesel` options.
+//! Drivers are heavy, so they are only pulled in by the features of this crate:
+//! run `cargo test -p validated_newtype_db_tests --features sqlx,diesel`.
+//!
+//! ## `sqlx`
+//! Values go through an in-memory SQLite database, and out-of-range values are rejected on decoding:
+//! ```
+//! # #[cfg(feature = "sqlx")] {
+//! # use validated_newtype::validated_newtype;
+//! use sqlx::SqlitePool;
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     i64 => pub Percent
+//!     in 0..=100;
+//!     sqlx;
+//! }
+//!
+//! # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+//! let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+//!
+//! let percent: Percent = sqlx::query_scalar("SELECT ?")
+//!     .bind(Percent::new(42).unwrap())
+//!     .fetch_one(&pool)
+//!     .await
+//!     .unwrap();
+//! assert_eq!(*percent, 42);
+//!
+//! let err = sqlx::query_scalar::<_, Percent>("SELECT 1337").fetch_one(&pool).await.unwrap_err();
+//! assert!(matches!(err, sqlx::Error::ColumnDecode { .. }));
+//! assert!(err.to_string().contains("value must be in range 0..=100"));
+//! # });
+//! # }
+//! ```
+//!
+//! ## `diesel`
+//! Newtype columns are bound as the base type in queries against Postgres:
+//! ```
+//! # #[cfg(feature = "diesel")] {
+//! # use validated_newtype::validated_newtype;
+//! use diesel::prelude::*;
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     i32 => pub Percent
+//!     in 0..=100;
+//!     diesel diesel::sql_types::Integer;
+//! }
+//!
+//! diesel::table! {
+//!     tasks (id) {
+//!         id -> Integer,
+//!         progress -> Integer,
+//!     }
+//! }
+//!
+//! #[derive(Queryable, Insertable)]
+//! #[diesel(table_name = tasks)]
+//! struct Task {
+//!     id: i32,
+//!     progress: Percent,
+//! }
+//!
+//! fn find_task<C>(conn: &mut C, id: i32) -> QueryResult<Task>
+//! where
+//!     C: diesel::connection::LoadConnection<Backend = diesel::pg::Pg>,
+//! {
+//!     tasks::table.find(id).first(conn)
+//! }
+//!
+//! let query = tasks::table.filter(tasks::progress.eq(Percent::new(100).unwrap()));
+//! let sql = diesel::debug_query::<diesel::pg::Pg, _>(&query).to_string();
+//! assert!(sql.ends_with(r#"WHERE ("tasks"."progress" = $1) -- binds: [100]"#));
+//! # }
+//! ```
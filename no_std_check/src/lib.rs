@@ -0,0 +1,56 @@
+//! Newtypes generated in a `#![no_std]` crate without `alloc`.
+//! Build with `cargo build -p validated_newtype_no_std_check` and with `--features serde`:
+//! anything in the generated code that needs an allocator fails to compile here.
+#![no_std]
+// `u32::is_multiple_of()` is only stable since Rust 1.87
+#![allow(clippy::manual_is_multiple_of)]
+
+use validated_newtype::validated_newtype;
+
+validated_newtype! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    u32 => pub Percent
+    in 0..=100;
+    display;
+    partial_ord;
+    default 0;
+}
+
+validated_newtype! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    u32 => pub Even
+    if |n: &u32| *n % 2 == 0;
+    error EvenError "number must be even";
+    hash;
+}
+
+validated_newtype! {
+    #[derive(Debug, Clone, Copy)]
+    &'a str => pub Name<'a>
+    non_empty;
+}
+
+validated_newtype! {
+    #[derive(Debug, Clone, Copy)]
+    f64 => pub Weight
+    finite;
+    total_order;
+}
+
+validated_newtype! {
+    #[derive(Debug, Clone, Copy)]
+    Percent => pub SmallPercent
+    if |p: &Percent| **p < 10;
+    error "percent must be below 10";
+    from [u32];
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Task<'a> {
+    #[serde(borrow)]
+    pub name: Name<'a>,
+    pub progress: Percent,
+    pub weight: Weight,
+    pub step: SmallPercent,
+}
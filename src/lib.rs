@@ -1,7 +1,8 @@
 // {{{ Documentation
 //! Simple checked newtype generator, primarily for use with [serde](https://serde.rs).
 //! Serde support (and dependency) may be disabled with `default_features = false`.
-//! This is `#![no_std]` library.
+//! This is `#![no_std]` library, and it doesn't need an allocator unless `alloc` feature
+//! (or a feature that implies it) is enabled.
 //!
 //! Usage:
 //! ```
@@ -26,167 +27,5198 @@
 //! Instances of generated newtype can be created only via [TryFrom] or [Deserialize],
 //! so they always hold valid data.
 //!
-//! ## Dynamic error generation
+//! Generated newtypes also implement [Serialize], which simply serializes the inner value,
+//! so the wire format is exactly the same as the base type's:
 //! ```
-//! # use core::convert::TryInto;
+//! # use core::convert::TryFrom;
 //! # use validated_newtype::validated_newtype;
 //! # use serde_json;
 //! validated_newtype! {
-//!     #[derive(Debug)]
+//!     #[derive(Debug, PartialEq)]
 //!     u32 => pub Percent
 //!     if |n: &u32| *n <= 100;
-//!     else |n: &u32| format!("number {} is not in range 0-100", n) => String
+//!     error "percent must be in range 0-100"
 //! }
 //!
-//! // Deserialize for newtypes uses try_into internally
-//! let x: Result<Percent, _> = 1337.try_into();
-//! assert!(x.is_err());
-//! assert_eq!(x.unwrap_err(), "number 1337 is not in range 0-100");
+//! let x = Percent::try_from(42).unwrap();
+//! let json = serde_json::to_string(&x).unwrap();
+//! assert_eq!(json, serde_json::to_string(&42u32).unwrap());
+//! assert_eq!(serde_json::from_str::<Percent>(&json).unwrap(), x);
 //! ```
-//! ## Manually implement [TryFrom]
+//!
+//! All generated trait impls are marked `#[automatically_derived]`, so lints and coverage tools
+//! treat them the same way as impls from `#[derive]`.
+//!
+//! ## Defining multiple newtypes
+//! Several definitions, each wrapped in braces, may be given in one invocation:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     {
+//!         #[derive(Debug)]
+//!         u32 => pub Percent
+//!         if |n: &u32| *n <= 100;
+//!         error "percent must be in range 0-100"
+//!     }
+//!     {
+//!         #[derive(Debug)]
+//!         String => pub NonEmptyName
+//!         if |s: &String| !s.is_empty();
+//!         error "name must not be empty"
+//!     }
+//!     {
+//!         #[derive(Debug)]
+//!         u16 => pub Port
+//!         in 1024..;
+//!         display;
+//!     }
+//! }
+//!
+//! assert_eq!(*Percent::new(42).unwrap(), 42);
+//! assert!(Percent::new(1337).is_err());
+//! assert_eq!(*NonEmptyName::new("ferris".to_string()).unwrap(), "ferris");
+//! assert!(NonEmptyName::new(String::new()).is_err());
+//! assert_eq!(Port::new(8080).unwrap().to_string(), "8080");
+//! assert!(Port::new(80).is_err());
+//! ```
+//!
+//! ## Derive macro
+//! With `derive` feature enabled, the same impls may be generated for a hand-written tuple struct
+//! with `#[derive(Validated)]`. Predicates and the error message are given in `#[validated(...)]`
+//! attributes, and [options](#options) are passed verbatim in `options(...)`.
+//! Predicates may be repeated and must all hold:
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use validated_newtype::Validated;
+//!
+//! fn is_percent(n: &u32) -> bool {
+//!     *n <= 100
+//! }
+//!
+//! #[derive(Debug, Validated)]
+//! #[validated(predicate = is_percent, error = "percent must be in range 0-100")]
+//! #[validated(options(display))]
+//! pub struct Percent(u32);
+//!
+//! #[derive(Debug, Validated)]
+//! #[validated(
+//!     predicate = |v: &Vec<T>| !v.is_empty(),
+//!     predicate = |v: &Vec<T>| v.len() <= 3,
+//!     error = "list must have 1-3 elements",
+//! )]
+//! pub struct Short<T>(Vec<T>);
+//!
+//! let x: Percent = serde_json::from_str("42").unwrap();
+//! assert_eq!(x.to_string(), "42");
+//! assert!(Percent::new(1337).is_err());
+//! assert_eq!(*Short::new(vec![1, 2]).unwrap(), [1, 2]);
+//! assert_eq!(Short::<u8>::new(vec![]).unwrap_err(), "list must have 1-3 elements");
+//! # }
+//! ```
+//!
+//! ## Wire format
+//! Generated newtypes are always (de)serialized exactly like their base type, as if they were
+//! `#[serde(transparent)]` structs. The optional `transparent` marker after the newtype name
+//! makes this explicit, but doesn't change the generated code:
 //! ```
-//! # use core::convert::TryFrom;
 //! # use validated_newtype::validated_newtype;
+//! # use serde::Deserialize;
 //! # use serde_json;
 //! validated_newtype! {
 //!     #[derive(Debug)]
-//!     u32 => pub Percent
+//!     u32 => pub Percent transparent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
 //! }
 //!
-//! impl TryFrom<u32> for Percent {
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     p: Percent,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct RawConfig {
+//!     p: u32,
+//! }
+//!
+//! let json = r#"{"p": 42}"#;
+//! let config: Config = serde_json::from_str(json).unwrap();
+//! let raw: RawConfig = serde_json::from_str(json).unwrap();
+//! assert_eq!(*config.p, raw.p);
+//! assert!(serde_json::from_str::<Config>(r#"{"p": 1337}"#).is_err());
+//! ```
+//!
+//! The marker works with every form of the definition:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent transparent
+//!     in 0..=100
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Name transparent
+//!     non_empty
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<T> => pub List<T>(pub(crate) _) transparent
+//!     len 1..=3
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Id transparent
+//! }
+//!
+//! impl TryFrom<u32> for Id {
 //!     type Error = &'static str;
 //!
 //!     fn try_from(val: u32) -> Result<Self, Self::Error> {
-//!         if val > 100 {
-//!             Err("percent must be in range 0-100")
-//!         } else {
-//!             Ok(Self(val))
-//!         }
+//!         Ok(Self(val))
 //!     }
 //! }
 //!
-//! let x: Percent = serde_json::from_str("42").unwrap();
-//! assert_eq!(*x, 42);
-//! let y: Result<Percent, _> = serde_json::from_str("1337");
-//! assert!(y.is_err());
+//! assert_eq!(*serde_json::from_str::<Percent>("42").unwrap(), 42);
+//! assert!(serde_json::from_str::<Name>(r#""""#).is_err());
+//! assert!(serde_json::from_str::<List<u8>>("[]").is_err());
+//! assert_eq!(*serde_json::from_str::<Id>("1337").unwrap(), 1337);
 //! ```
 //!
-//! [TryFrom]: https://doc.rust-lang.org/stable/core/convert/trait.TryFrom.html
-//! [Deserialize]: https://docs.rs/serde/latest/serde/trait.Deserialize.html
-// }}}
-
-#![no_std]
-
-#[cfg(feature = "serde")]
-#[doc(hidden)]
-#[macro_export]
-macro_rules! add_deserialize {
-    ($type:ident, $parent:ty) => {
-        impl<'de> serde::Deserialize<'de> for $type {
-            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-                use core::convert::TryInto as _;
-                use serde::de::Error as _;
-                <$parent as serde::Deserialize>::deserialize(deserializer)?
-                    .try_into()
-                    .map_err(D::Error::custom)
-            }
-        }
+//! Since newtypes don't add any structure of their own, they work with `#[serde(flatten)]`
+//! and untagged enums, where serde buffers the input before deserializing the fields:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! # use serde::Deserialize;
+//! # use serde_json;
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     u32 => pub Percent transparent
+//! #     if |n: &u32| *n <= 100;
+//! #     error "percent must be in range 0-100"
+//! # }
+//! #[derive(Deserialize)]
+//! struct Limits {
+//!     cpu: Percent,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Service {
+//!     name: String,
+//!     #[serde(flatten)]
+//!     limits: Limits,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! #[serde(untagged)]
+//! enum Load {
+//!     Percent(Percent),
+//!     Raw(u32),
+//! }
+//!
+//! let service: Service = serde_json::from_str(r#"{"name": "db", "cpu": 42}"#).unwrap();
+//! assert_eq!((service.name.as_str(), *service.limits.cpu), ("db", 42));
+//! assert!(serde_json::from_str::<Service>(r#"{"name": "db", "cpu": 1337}"#).is_err());
+//!
+//! assert!(matches!(serde_json::from_str(r#"42"#).unwrap(), Load::Percent(p) if *p == 42));
+//! assert!(matches!(serde_json::from_str(r#"1337"#).unwrap(), Load::Raw(1337)));
+//! ```
+//!
+//! When the field type can't be changed (e.g. in generated code), `deserialize_checked` applies
+//! the same validation to a field of the base type via `#[serde(deserialize_with = "...")]`:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! # use serde::Deserialize;
+//! # use serde_json;
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     u32 => pub Percent
+//! #     if |n: &u32| *n <= 100;
+//! #     error "percent must be in range 0-100"
+//! # }
+//! #[derive(Debug, Deserialize)]
+//! struct Limits {
+//!     #[serde(deserialize_with = "Percent::deserialize_checked")]
+//!     cpu: u32,
+//! }
+//!
+//! let limits: Limits = serde_json::from_str(r#"{"cpu": 42}"#).unwrap();
+//! assert_eq!(limits.cpu, 42);
+//! let err = serde_json::from_str::<Limits>(r#"{"cpu": 1337}"#).unwrap_err();
+//! assert!(err.to_string().starts_with("percent must be in range 0-100"));
+//! ```
+//!
+//! With `alloc` feature enabled, `deserialize_vec_checked` deserializes a sequence of newtypes,
+//! reporting all invalid elements at once instead of stopping at the first one:
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use validated_newtype::validated_newtype;
+//! # use serde::Deserialize;
+//! # use serde_json;
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     u32 => pub Percent
+//! #     if |n: &u32| *n <= 100;
+//! #     error "percent must be in range 0-100"
+//! # }
+//! #[derive(Debug, Deserialize)]
+//! struct Form {
+//!     #[serde(deserialize_with = "Percent::deserialize_vec_checked")]
+//!     shares: Vec<Percent>,
+//! }
+//!
+//! let form: Form = serde_json::from_str(r#"{"shares": [10, 20]}"#).unwrap();
+//! assert_eq!(form.shares.len(), 2);
+//!
+//! let err = serde_json::from_str::<Form>(r#"{"shares": [10, 200, 20, 300]}"#).unwrap_err();
+//! assert!(err.to_string().starts_with(
+//!     "[1]: percent must be in range 0-100, [3]: percent must be in range 0-100"
+//! ));
+//! # }
+//! ```
+//!
+//! ## JSON schema
+//! With `schemars` feature enabled, `json_schema` option implements [JsonSchema] with the same schema
+//! as the base type. Base type must implement [JsonSchema], so nested newtypes need the option as well:
+//! ```
+//! # #[cfg(feature = "schemars")] {
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     json_schema;
+//! }
+//!
+//! assert_eq!(schemars::schema_for!(Percent), schemars::schema_for!(u32));
+//! # }
+//! ```
+//!
+//! When newtype is defined with `in` or `chars` [range clause](#ranges), bounds are added to the schema:
+//! ```
+//! # #[cfg(feature = "schemars")] {
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     in 0..=100;
+//!     json_schema;
+//! }
+//!
+//! let schema = schemars::schema_for!(Percent);
+//! assert_eq!(schema.get("minimum"), Some(&0.into()));
+//! assert_eq!(schema.get("maximum"), Some(&100.into()));
+//! # }
+//! ```
+//!
+//! ## Borsh
+//! With `borsh` feature enabled, `borsh` option implements [BorshSerialize] and [BorshDeserialize]
+//! with the same encoding as the base type. Decoded values are checked, and failure is reported
+//! as an `InvalidData` I/O error with the [Display] representation of the error:
+//! ```
+//! # #[cfg(feature = "borsh")] {
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, PartialEq)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     borsh;
+//! }
+//!
+//! let x = Percent::try_from(42).unwrap();
+//! let bytes = borsh::to_vec(&x).unwrap();
+//! assert_eq!(bytes, borsh::to_vec(&42u32).unwrap());
+//! assert_eq!(borsh::from_slice::<Percent>(&bytes).unwrap(), x);
+//!
+//! let err = borsh::from_slice::<Percent>(&borsh::to_vec(&1337u32).unwrap()).unwrap_err();
+//! assert_eq!(err.kind(), borsh::io::ErrorKind::InvalidData);
+//! assert_eq!(err.to_string(), "percent must be in range 0-100");
+//! # }
+//! ```
+//!
+//! ## Miniserde
+//! With `miniserde` feature enabled, `miniserde` option implements `miniserde`'s `Serialize`
+//! and `Deserialize`, independently of `serde` feature. Decoded values are checked.
+//! Only scalar base types (numbers, strings, booleans) are supported:
+//! ```
+//! # #[cfg(feature = "miniserde")] {
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, PartialEq)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     miniserde;
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Name
+//!     non_empty;
+//!     miniserde;
+//! }
+//!
+//! let x = Percent::new(42).unwrap();
+//! assert_eq!(miniserde::json::to_string(&x), "42");
+//! assert_eq!(miniserde::json::from_str::<Percent>("42").unwrap(), x);
+//! assert!(miniserde::json::from_str::<Percent>("1337").is_err());
+//!
+//! assert_eq!(*miniserde::json::from_str::<Name>(r#""Ferris""#).unwrap(), "Ferris");
+//! assert!(miniserde::json::from_str::<Name>(r#""""#).is_err());
+//! # }
+//! ```
+//!
+//! ## Bytemuck
+//! With `bytemuck` feature enabled, `bytemuck` option implies [`transparent_repr`](#options)
+//! and implements [TransparentWrapper] for it, allowing zero-copy casts between references
+//! and slices of the newtype and the base type. This is sound because the newtype never has
+//! any fields besides the inner value.
+//!
+//! Peeling newtypes is always fine, but wrapping **bypasses validation**: like
+//! [`new_unchecked()`](#skipping-validation), it's up to the caller to only wrap valid values:
+//! ```
+//! # #[cfg(feature = "bytemuck")] {
+//! # use validated_newtype::validated_newtype;
+//! use bytemuck::TransparentWrapper;
+//!
+//! validated_newtype! {
+//!     #[derive(Debug, Clone, Copy)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     bytemuck;
+//! }
+//!
+//! let percents = [Percent::new(10).unwrap(), Percent::new(20).unwrap()];
+//! let raw: &[u32] = Percent::peel_slice(&percents);
+//! assert_eq!(raw, [10, 20]);
+//!
+//! // Not checked! `1337` must not be wrapped outside of trusted code
+//! let unchecked: &[Percent] = Percent::wrap_slice(&[42, 1337]);
+//! assert_eq!(*unchecked[1], 1337);
+//! # }
+//! ```
+//!
+//! Derive macro can't change the struct definition, so with `#[derive(Validated)]` the struct
+//! must be marked `#[repr(transparent)]` explicitly, and `bytemuck` option is rejected otherwise:
+//! ```
+//! # #[cfg(all(feature = "derive", feature = "bytemuck"))] {
+//! use bytemuck::TransparentWrapper;
+//! use validated_newtype::Validated;
+//!
+//! #[derive(Debug, Validated)]
+//! #[validated(predicate = |n: &u32| *n <= 100, error = "percent must be in range 0-100")]
+//! #[validated(options(bytemuck))]
+//! #[repr(transparent)]
+//! pub struct Percent(u32);
+//!
+//! assert_eq!(*Percent::peel_ref(&Percent::new(42).unwrap()), 42);
+//! # }
+//! ```
+//!
+//! ## `sqlx` integration
+//! With `sqlx` feature enabled, `sqlx` option implements [sqlx::Type], [sqlx::Encode]
+//! and [sqlx::Decode] for every database the base type supports, with the same column type.
+//! Decoded values are checked, so invalid rows are reported as decoding errors instead of
+//! producing invalid newtypes. `sqlx-postgres` feature additionally enables the Postgres driver of `sqlx`:
+//! ```
+//! # #[cfg(feature = "sqlx-postgres")] {
+//! # use validated_newtype::validated_newtype;
+//! use sqlx::PgPool;
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     i64 => pub Percent
+//!     in 0..=100;
+//!     sqlx;
+//! }
+//!
+//! async fn set_progress(pool: &PgPool, id: i64, progress: Percent) -> sqlx::Result<()> {
+//!     sqlx::query("UPDATE tasks SET progress = $1 WHERE id = $2")
+//!         .bind(progress)
+//!         .bind(id)
+//!         .execute(pool)
+//!         .await?;
+//!     Ok(())
+//! }
+//!
+//! // Fails with `sqlx::Error::ColumnDecode` if the stored value is out of range
+//! async fn progress(pool: &PgPool, id: i64) -> sqlx::Result<Percent> {
+//!     sqlx::query_scalar("SELECT progress FROM tasks WHERE id = $1")
+//!         .bind(id)
+//!         .fetch_one(pool)
+//!         .await
+//! }
+//! # }
+//! ```
+//!
+//! ## Diesel
+//! With `diesel` feature enabled, `diesel <SQL type>` option implements [ToSql], [FromSql],
+//! [Queryable] and [AsExpression] for the given SQL type and any backend the base type supports it for,
+//! so newtypes may be used as columns of that type. [FromSql] checks the decoded value,
+//! so invalid rows are reported as deserialization errors instead of producing invalid newtypes.
+//! Base type must map to the given SQL type, e.g. `i32` to `Integer` or `String` to `Text`:
+//! ```
+//! # #[cfg(feature = "diesel")] {
+//! # use validated_newtype::validated_newtype;
+//! use diesel::prelude::*;
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     i32 => pub Percent
+//!     in 0..=100;
+//!     diesel diesel::sql_types::Integer;
+//! }
+//!
+//! diesel::table! {
+//!     tasks (id) {
+//!         id -> Integer,
+//!         progress -> Integer,
+//!     }
+//! }
+//!
+//! #[derive(Queryable, Insertable)]
+//! #[diesel(table_name = tasks)]
+//! struct Task {
+//!     id: i32,
+//!     progress: Percent,
+//! }
+//!
+//! let done = tasks::table.filter(tasks::progress.eq(Percent::new(100).unwrap()));
+//! # let _ = done;
+//! # }
+//! ```
+//!
+//! ## `validator` integration
+//! With `validator` feature enabled, `validator` option implements [Validate], so newtypes may be used as nested fields
+//! in structs deriving it. Since newtypes always hold valid data, `validate()` always returns `Ok(())`
+//! and contributes nothing to [ValidationErrors].
+//!
+//! When predicate is given, `validate_custom()` is also generated. It checks a value of the base type
+//! and may be used as a custom validator, e.g. `#[validate(custom(function = "Percent::validate_custom"))]`.
+//! The error code is the newtype name, and the message is the [Display] representation of the error:
+//! ```
+//! # #[cfg(feature = "validator")] {
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! use validator::Validate;
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     validator;
+//! }
+//!
+//! assert!(Percent::try_from(42).unwrap().validate().is_ok());
+//! assert!(Percent::validate_custom(&42).is_ok());
+//! let err = Percent::validate_custom(&1337).unwrap_err();
+//! assert_eq!(err.code, "Percent");
+//! assert_eq!(err.message.unwrap(), "percent must be in range 0-100");
+//! # }
+//! ```
+//!
+//! [JsonSchema], borsh and [Validate] impls are only generated on request, so enabling the features
+//! doesn't affect newtypes over types that don't implement the corresponding traits:
+//! ```
+//! # #[cfg(all(feature = "schemars", feature = "borsh", feature = "validator"))] {
+//! # use validated_newtype::validated_newtype;
+//! #[derive(Debug, serde::Serialize, serde::Deserialize)]
+//! pub struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Point => pub OnDiagonal
+//!     if |p: &Point| p.x == p.y;
+//!     error "point must be on the diagonal"
+//! }
+//!
+//! assert!(OnDiagonal::new(Point { x: 1, y: 2 }).is_err());
+//! # }
+//! ```
+//!
+//! ## Property testing
+//! With `proptest` feature enabled, `proptest` option implements [proptest::arbitrary::Arbitrary]
+//! for the newtype, so it only generates valid values. Newtype must implement [Debug].
+//!
+//! For newtypes defined with `in` [range clause](#ranges) values are generated directly from the range.
+//! Otherwise, values of the base type are generated and rejected if they don't satisfy the predicate.
+//! If predicate is very restrictive, most values are rejected, and test fails with "Too many local rejects".
+//! In that case consider using a custom strategy instead.
+//! ```
+//! # #[cfg(feature = "proptest")] {
+//! # use validated_newtype::validated_newtype;
+//! use proptest::prelude::*;
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     in 0..=100;
+//!     proptest;
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Even
+//!     if |n: &u32| n % 2 == 0;
+//!     error "number must be even";
+//!     proptest;
+//! }
+//!
+//! // Rejection sampling is fine here, since ~40% of `u8` values are valid
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u8 => pub Progress
+//!     if |n: &u8| *n <= 100;
+//!     error "progress must be in range 0-100";
+//!     proptest;
+//! }
+//!
+//! proptest!(|(percent: Percent, even: Even, progress: Progress)| {
+//!     prop_assert!(*percent <= 100);
+//!     prop_assert!(*even % 2 == 0);
+//!     prop_assert!(*progress <= 100);
+//! });
+//! # }
+//! ```
+//!
+//! Similarly, with `quickcheck` feature enabled, `quickcheck` option implements [quickcheck::Arbitrary].
+//! Values of the base type are generated until one satisfies the predicate, and generation panics
+//! after 10000 failed attempts. Shrinking shrinks the inner value and skips invalid candidates.
+//! Newtype must implement [Clone]:
+//! ```
+//! # #[cfg(feature = "quickcheck")] {
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, Clone)]
+//!     u8 => pub Percent
+//!     in 0..=100;
+//!     quickcheck;
+//! }
+//!
+//! fn prop(percent: Percent) -> bool {
+//!     use quickcheck::Arbitrary;
+//!     *percent <= 100 && percent.shrink().all(|shrunk| *shrunk <= 100)
+//! }
+//!
+//! quickcheck::quickcheck(prop as fn(Percent) -> bool);
+//! # }
+//! ```
+//!
+//! For fuzzing, `arbitrary` option (requires `arbitrary` feature) implements [arbitrary::Arbitrary].
+//! The base value is read from the input and validated, so invalid inputs are rejected with
+//! `IncorrectFormat` error instead of producing an invalid newtype:
+//! ```
+//! # #[cfg(feature = "arbitrary")] {
+//! # use validated_newtype::validated_newtype;
+//! use arbitrary::{Arbitrary, Unstructured};
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u8 => pub Percent
+//!     in 0..=100;
+//!     arbitrary;
+//! }
+//!
+//! let mut u = Unstructured::new(&[42, 200]);
+//! assert_eq!(*Percent::arbitrary(&mut u).unwrap(), 42);
+//! assert!(matches!(Percent::arbitrary(&mut u), Err(arbitrary::Error::IncorrectFormat)));
+//!
+//! for byte in 0..=u8::MAX {
+//!     match Percent::arbitrary(&mut Unstructured::new(&[byte])) {
+//!         Ok(percent) => assert!(*percent <= 100),
+//!         Err(err) => assert!(byte > 100 && matches!(err, arbitrary::Error::IncorrectFormat)),
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! ## Accessing the inner value
+//! Newtypes implement [Deref] and [AsRef] to the base type, and the inner value may be moved out
+//! with `into_inner()` or [Into]:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! let x = Percent::try_from(42).unwrap();
+//! assert_eq!(*x, 42);
+//! assert_eq!(x.into_inner(), 42);
+//!
+//! let raw: u32 = Percent::try_from(42).unwrap().into();
+//! assert_eq!(raw, 42);
+//! assert_eq!(*Percent::try_from(raw).unwrap(), 42);
+//!
+//! fn double(n: impl Into<u32>) -> u32 {
+//!     n.into() * 2
+//! }
+//! assert_eq!(double(Percent::try_from(42).unwrap()), 84);
+//!
+//! validated_newtype! {
+//!     String => pub Name
+//!     if |s: &String| !s.is_empty();
+//!     error "name must not be empty"
+//! }
+//!
+//! // No cloning required
+//! let name: String = Name::try_from("Ferris".to_string()).unwrap().into_inner();
+//! assert_eq!(name, "Ferris");
+//! ```
+//!
+//! [Deref] exposes the whole API of the base type, which may be undesirable for opaque types.
+//! `no_deref` option removes it, and generates `get()` accessor instead:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Token
+//!     if |s: &String| s.len() == 32;
+//!     error "token must be 32 characters long";
+//!     no_deref;
+//! }
+//!
+//! let token = Token::new("0".repeat(32)).unwrap();
+//! assert_eq!(token.get().len(), 32);
+//! ```
+//!
+//! ```compile_fail
+//! # use validated_newtype::validated_newtype;
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     String => pub Token
+//! #     if |s: &String| s.len() == 32;
+//! #     error "token must be 32 characters long";
+//! #     no_deref;
+//! # }
+//! let token = Token::new("0".repeat(32)).unwrap();
+//! let len = token.len(); // no `Deref<Target = String>`
+//! ```
+//!
+//! ## Constructing without [TryFrom]
+//! When predicate is given, newtypes also get inherent `try_new()` constructor (and its alias `new()`),
+//! which doesn't require importing any traits:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! assert_eq!(*Percent::try_new(42).unwrap(), 42);
+//! assert_eq!(Percent::try_new(1337).unwrap_err(), "percent must be in range 0-100");
+//! assert_eq!(*Percent::new(42).unwrap(), 42);
+//! ```
+//!
+//! ## Checking without constructing
+//! When predicate is given, `validate()` checks a value of the base type without consuming it,
+//! and `is_valid()` reports the result as `bool`. All generated constructors go through `validate()`.
+//! Neither is generated without a predicate, since there's nothing to check then:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! assert_eq!(Percent::validate(&42), Ok(()));
+//! assert_eq!(Percent::validate(&200), Err("percent must be in range 0-100"));
+//!
+//! let valid: Vec<u32> = vec![0, 42, 200, 100].into_iter().filter(Percent::is_valid).collect();
+//! assert_eq!(valid, [0, 42, 100]);
+//! ```
+//!
+//! ## Mapping
+//! `map_checked()` transforms the inner value and checks the predicate on the result,
+//! so arithmetic can't silently produce an invalid value:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! let percent = Percent::new(42).unwrap();
+//! assert_eq!(*percent.map_checked(|n| n + 10).unwrap(), 52);
+//!
+//! let percent = Percent::new(95).unwrap();
+//! assert_eq!(percent.map_checked(|n| n + 10).unwrap_err(), "percent must be in range 0-100");
+//! ```
+//!
+//! ## Skipping validation
+//! If the value is already known to be valid, predicate check may be skipped with
+//! `const unsafe fn new_unchecked()`. Caller is responsible for upholding the invariant:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! // SAFETY: 42 is in range 0-100
+//! let x = unsafe { Percent::new_unchecked(42) };
+//! assert_eq!(*x, 42);
+//!
+//! // SAFETY: 100 is in range 0-100
+//! const FULL: Percent = unsafe { Percent::new_unchecked(100) };
+//! assert_eq!(*FULL, 100);
+//! ```
+//!
+//! ## Const construction
+//! `if const <path>` takes a `const fn` predicate and additionally generates
+//! `const fn new_const()`, which returns `None` if the predicate fails. Panicking on `None`
+//! in a constant turns invalid values into compile-time errors:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! const fn is_percent(n: &u32) -> bool {
+//!     *n <= 100
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if const is_percent;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! const HALF: Percent = match Percent::new_const(50) {
+//!     Some(percent) => percent,
+//!     None => panic!("invalid percent"),
+//! };
+//!
+//! assert_eq!(*HALF, 50);
+//! assert!(Percent::new_const(1337).is_none());
+//! assert!(Percent::new(1337).is_err());
+//! ```
+//!
+//! ```compile_fail
+//! # use validated_newtype::validated_newtype;
+//! # const fn is_percent(n: &u32) -> bool {
+//! #     *n <= 100
+//! # }
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     u32 => pub Percent
+//! #     if const is_percent;
+//! #     error "percent must be in range 0-100"
+//! # }
+//! const INVALID: Percent = match Percent::new_const(1337) {
+//!     Some(percent) => percent,
+//!     None => panic!("invalid percent"),
+//! };
+//! # let _ = INVALID;
+//! ```
+//!
+//! Since a rejected value is dropped at compile time, base type must not have drop glue
+//! (e.g. `String` can't be used).
+//!
+//! [valid!] macro wraps this pattern:
+//! ```
+//! # use validated_newtype::{valid, validated_newtype};
+//! # const fn is_percent(n: &u32) -> bool {
+//! #     *n <= 100
+//! # }
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     u32 => pub Percent
+//! #     if const is_percent;
+//! #     error "percent must be in range 0-100"
+//! # }
+//! const FULL: Percent = valid!(Percent, 100);
+//! let half = valid!(Percent, 50);
+//! assert_eq!((*FULL, *half), (100, 50));
+//! ```
+//!
+//! ```compile_fail
+//! # use validated_newtype::{valid, validated_newtype};
+//! # const fn is_percent(n: &u32) -> bool {
+//! #     *n <= 100
+//! # }
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     u32 => pub Percent
+//! #     if const is_percent;
+//! #     error "percent must be in range 0-100"
+//! # }
+//! let invalid = valid!(Percent, 200);
+//! ```
+//!
+//! ## Field visibility
+//! The inner field is private by default, so code outside of the defining module can't construct
+//! the newtype bypassing validation. Visibility of the field may be set explicitly
+//! with `(<visibility> _)` after the newtype name, allowing trusted code to construct and
+//! destructure the newtype directly:
+//! ```
+//! mod domain {
+//!     # use validated_newtype::validated_newtype;
+//!     validated_newtype! {
+//!         #[derive(Debug)]
+//!         u32 => pub Percent(pub(crate) _)
+//!         if |n: &u32| *n <= 100;
+//!         error "percent must be in range 0-100"
+//!     }
+//!
+//!     validated_newtype! {
+//!         #[derive(Debug)]
+//!         Vec<T> => pub NonEmpty<T>(pub(crate) Vec<T>)
+//!         if |v: &Vec<T>| !v.is_empty();
+//!         error "vec must not be empty"
+//!     }
+//! }
+//!
+//! let domain::Percent(n) = serde_json::from_str("42").unwrap();
+//! assert_eq!(n, 42);
+//!
+//! let domain::NonEmpty(v) = serde_json::from_str::<domain::NonEmpty<u32>>("[1]").unwrap();
+//! assert_eq!(v, [1]);
+//! ```
+//!
+//! Attributes and doc comments may be put before the visibility, and are applied to the field:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent(
+//!         /// Always in range 0-100.
+//!         #[allow(dead_code)]
+//!         pub(crate) _
+//!     )
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! assert_eq!(Percent::new(42).unwrap().0, 42);
+//! ```
+//!
+//! The base type may be repeated instead of `_`, e.g. `u32 => pub Percent(pub(crate) u32)`.
+//! It must match the base type exactly:
+//! ```compile_fail
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     u32 => pub Percent(pub(crate) u64)
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//! ```
+//!
+//! Any code that can see the field can also put an invalid value there, so `pub(crate)`
+//! or narrower is recommended. Making the field as visible as the newtype itself
+//! undermines the guarantee entirely. Without explicit visibility, the field is private:
+//! ```compile_fail
+//! mod domain {
+//!     # use validated_newtype::validated_newtype;
+//!     validated_newtype! {
+//!         #[derive(Debug)]
+//!         u32 => pub Percent
+//!         if |n: &u32| *n <= 100;
+//!         error "percent must be in range 0-100"
+//!     }
+//! }
+//!
+//! let percent = domain::Percent(1337);
+//! ```
+//!
+//! ## Options
+//! Additional impls may be requested by listing options after the predicate, separated by `;`.
+//!
+//! `from_str` implements [FromStr] for newtypes over types that implement it. The string is parsed
+//! into the base type first, and then checked with the predicate. Errors are reported as [FromStrError]:
+//! ```
+//! # use validated_newtype::{validated_newtype, FromStrError};
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     from_str;
+//! }
+//!
+//! assert_eq!(*"42".parse::<Percent>().unwrap(), 42);
+//! assert!(matches!("foo".parse::<Percent>(), Err(FromStrError::Parse(_))));
+//! assert!(matches!("1337".parse::<Percent>(), Err(FromStrError::Invalid(_))));
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Name
+//!     if |s: &String| !s.is_empty();
+//!     error "name must not be empty";
+//!     from_str;
+//! }
+//!
+//! assert_eq!(*"Ferris".parse::<Name>().unwrap(), "Ferris");
+//! assert_eq!("".parse::<Name>().unwrap_err(), FromStrError::Invalid("name must not be empty"));
+//! ```
+//!
+//! `from [<type>, ...]` implements [TryFrom] from other types, which are converted into the base type
+//! with its own [TryFrom] impl first, so both lossless and narrowing conversions work.
+//! Errors are reported as [ConversionError], with `Convert` variant for conversion failures.
+//! `&str` is special: it's parsed with [FromStr], and errors are reported as [FromStrError]:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::{validated_newtype, ConversionError, FromStrError};
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u8 => pub Percent
+//!     if |n: &u8| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     from [bool, u32, &str];
+//! }
+//!
+//! assert_eq!(*Percent::try_from(true).unwrap(), 1);
+//! assert_eq!(*Percent::try_from(42u32).unwrap(), 42);
+//! assert!(matches!(Percent::try_from(1337u32), Err(ConversionError::Convert(_))));
+//! assert_eq!(Percent::try_from(200u32).unwrap_err(), ConversionError::Invalid("percent must be in range 0-100"));
+//!
+//! assert_eq!(*Percent::try_from("42").unwrap(), 42);
+//! assert!(matches!(Percent::try_from("foo"), Err(FromStrError::Parse(_))));
+//! assert_eq!(Percent::try_from("200").unwrap_err(), FromStrError::Invalid("percent must be in range 0-100"));
+//! ```
+//!
+//! `from_newtype [<type>, ...]` is meant for converting between newtypes sharing the base type.
+//! Source types must losslessly convert into the base type (which every newtype does),
+//! so only the predicate of the target type is checked, and its error type is used as is:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Ratio
+//!     if |n: &u32| *n <= 10;
+//!     error "ratio must be in range 0-10";
+//!     from_newtype [Percent];
+//! }
+//!
+//! assert_eq!(*Ratio::try_from(Percent::new(5).unwrap()).unwrap(), 5);
+//! assert_eq!(Ratio::try_from(Percent::new(50).unwrap()).unwrap_err(), "ratio must be in range 0-10");
+//! ```
+//!
+//! `display` implements [Display] by forwarding to the inner value, including formatting flags.
+//! It's opt-in, since not every base type implements [Display]:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     display;
+//! }
+//!
+//! assert_eq!(format!("{}", Percent::new(42).unwrap()), "42");
+//! assert_eq!(format!("{:>4}%", Percent::new(42).unwrap()), "  42%");
+//! ```
+//!
+//! `partial_eq` implements [PartialEq] between the newtype and the base type, in both directions.
+//! It's opt-in, since not every base type implements [PartialEq]:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     partial_eq;
+//! }
+//!
+//! let percent = Percent::new(42).unwrap();
+//! assert_eq!(percent, 42);
+//! assert_eq!(42, percent);
+//! assert_ne!(percent, 43);
+//! assert_ne!(43, percent);
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<u32> => pub NonEmpty
+//!     if |v: &Vec<u32>| !v.is_empty();
+//!     error "list must not be empty";
+//!     partial_eq;
+//! }
+//!
+//! assert_eq!(NonEmpty::new(vec![1, 2]).unwrap(), vec![1, 2]);
+//! assert_eq!(vec![1, 2], NonEmpty::new(vec![1, 2]).unwrap());
+//! ```
+//!
+//! `partial_ord` additionally implements [PartialOrd] against the base type, in both directions.
+//! It includes everything `partial_eq` generates, so `partial_eq` may be left out:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     partial_ord;
+//! }
+//!
+//! let percent = Percent::new(42).unwrap();
+//! assert!(percent == 42);
+//! assert!(percent > 10);
+//! assert!(percent <= 42);
+//! assert!(50 > percent);
+//! assert!(42 >= percent);
+//! assert!(!(percent < 42));
+//! ```
+//!
+//! Giving both is redundant, but allowed in any order:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     u32 => pub Percent
+//!     in 0..=100;
+//!     partial_eq;
+//!     partial_ord;
+//! }
+//!
+//! validated_newtype! {
+//!     u32 => pub Permille
+//!     in 0..=1000;
+//!     partial_ord;
+//!     partial_eq;
+//! }
+//!
+//! assert!(Percent::new(42).unwrap() == 42);
+//! assert!(Permille::new(420).unwrap() > 42);
+//! ```
+//!
+//! `total_order` implements [PartialEq], [Eq], [PartialOrd], [Ord] and [Hash] for `f32` and `f64`
+//! newtypes, which can't derive them. It's only sound when all values are comparable, so it requires
+//! [`finite` or `non_nan`](#ranges) predicate, which rule out NaN. `-0.0` and `0.0` are equal and hash the same:
+//! ```
+//! # use std::collections::{BTreeSet, HashSet};
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, Clone, Copy)]
+//!     f64 => pub Weight
+//!     finite;
+//!     total_order;
+//! }
+//!
+//! let weights = [2.5, 0.0, -0.0, 1.0, 2.5].map(|w| Weight::new(w).unwrap());
+//!
+//! let unique: HashSet<Weight> = weights.iter().copied().collect();
+//! assert_eq!(unique.len(), 3);
+//!
+//! let sorted: BTreeSet<Weight> = weights.iter().copied().collect();
+//! assert_eq!(sorted.iter().map(|w| **w).collect::<Vec<_>>(), [0.0, 1.0, 2.5]);
+//! assert!(Weight::new(1.0).unwrap() < Weight::new(2.5).unwrap());
+//! assert!(Weight::new(f64::NAN).is_err());
+//! ```
+//!
+//! Other predicates may let NaN through, so they are rejected:
+//! ```compile_fail
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     f64 => pub Weight
+//!     if |w: &f64| *w >= 0.0;
+//!     error "weight must be non-negative";
+//!     total_order;
+//! }
+//! ```
+//!
+//! `hash` implements [Hash] by forwarding to the inner value, which is consistent with
+//! derived [PartialEq]. It's an alternative to `#[derive(Hash)]` when derives can't be edited:
+//! ```
+//! # use std::collections::HashSet;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, PartialEq, Eq)]
+//!     String => pub Tag
+//!     if |s: &String| !s.is_empty();
+//!     error "tag must not be empty";
+//!     hash;
+//! }
+//!
+//! let tags: HashSet<Tag> = ["rust", "serde", "rust"]
+//!     .iter()
+//!     .map(|tag| Tag::new(tag.to_string()).unwrap())
+//!     .collect();
+//! assert_eq!(tags.len(), 2);
+//! assert!(tags.contains(&Tag::new("serde".to_string()).unwrap()));
+//! ```
+//!
+//! Similarly, `ord` implements [PartialOrd] and [Ord] by forwarding to the inner value,
+//! without bounds on generic parameters that `#[derive]` would add. [PartialEq] and [Eq]
+//! still have to be provided:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, PartialEq, Eq)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     ord;
+//! }
+//!
+//! let raw = vec![42, 7, 100, 0, 7];
+//! let mut percents: Vec<Percent> = raw.iter().map(|&n| Percent::new(n).unwrap()).collect();
+//! percents.sort();
+//!
+//! let mut sorted = raw.clone();
+//! sorted.sort();
+//! assert_eq!(percents.into_iter().map(Percent::into_inner).collect::<Vec<_>>(), sorted);
+//! ```
+//!
+//! `try_from_ref` implements [TryFrom] from a reference to the base type, cloning the value:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     try_from_ref;
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Username
+//!     if |s: &String| !s.is_empty();
+//!     error "username must not be empty";
+//!     try_from_ref;
+//! }
+//!
+//! let n = 42;
+//! assert_eq!(*Percent::try_from(&n).unwrap(), 42);
+//! assert!(Percent::try_from(&1337).is_err());
+//!
+//! let name = "ferris".to_string();
+//! assert_eq!(*Username::try_from(&name).unwrap(), name);
+//! assert!(Username::try_from(&String::new()).is_err());
+//! ```
+//!
+//! `String` newtypes may also accept `&str` with `from [&str]` option. Since predicates take `&String`,
+//! the string is copied before the check:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Username
+//!     if |s: &String| !s.is_empty();
+//!     error "username must not be empty";
+//!     try_from_ref;
+//!     from [&str];
+//! }
+//!
+//! assert_eq!(*Username::try_from("ferris").unwrap(), "ferris");
+//! assert_eq!(*Username::try_from(&"ferris".to_string()).unwrap(), "ferris");
+//! assert!(Username::try_from("").is_err());
+//! ```
+//!
+//! `modify` allows editing the inner value in place with `modify()`. The closure gets a copy
+//! of the inner value, which replaces it only if the predicate still holds.
+//! Base type must implement [Clone]:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<u32> => pub Small
+//!     if |v: &Vec<u32>| v.len() <= 2;
+//!     error "at most two elements are allowed";
+//!     modify;
+//! }
+//!
+//! let mut small = Small::new(vec![1]).unwrap();
+//! small.modify(|v| v.push(2)).unwrap();
+//! assert_eq!(*small, [1, 2]);
+//!
+//! assert_eq!(small.modify(|v| v.push(3)), Err("at most two elements are allowed"));
+//! assert_eq!(*small, [1, 2]);
+//! ```
+//!
+//! `sanitize <closure>` normalizes the value before the predicate is checked, and the sanitized
+//! value is what gets stored. It applies to [TryFrom], [Deserialize] and `validate()` alike;
+//! the latter only gets a reference, so it sanitizes a copy and the base type must implement [Clone]:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Username
+//!     if |s: &String| !s.is_empty() && s.chars().all(|c| c.is_ascii_lowercase());
+//!     error "username must consist of lowercase letters";
+//!     sanitize |s: String| s.trim().to_lowercase();
+//! }
+//!
+//! let user: Username = serde_json::from_str(r#""  ADMIN  ""#).unwrap();
+//! assert_eq!(*user, "admin");
+//! assert_eq!(*Username::new(" Ferris".to_string()).unwrap(), "ferris");
+//! assert!(Username::new("   ".to_string()).is_err());
+//! assert!(Username::is_valid(&"ADMIN".to_string()));
+//! assert!(Username::validate(&"   ".to_string()).is_err());
+//! ```
+//!
+//! `default <value>` implements [Default], checking the value with the predicate.
+//! Invalid default is a programming error, so `default()` panics on it instead of returning an invalid newtype:
+//! ```
+//! # use serde::Deserialize;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, PartialEq)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     default 0;
+//! }
+//!
+//! assert_eq!(*Percent::default(), 0);
+//!
+//! #[derive(Deserialize)]
+//! struct Progress {
+//!     #[serde(default)]
+//!     done: Percent,
+//! }
+//!
+//! let progress: Progress = serde_json::from_str("{}").unwrap();
+//! assert_eq!(progress.done, Percent::default());
+//! ```
+//!
+//! ```should_panic
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     default 1337;
+//! }
+//!
+//! let _ = Percent::default(); // panics
+//! ```
+//!
+//! `default` also generates `deserialize_or_default` for `#[serde(deserialize_with = "...")]`, which replaces
+//! invalid values with the default. Note that it **silently discards** invalid input, and only
+//! values of the wrong type are still reported as errors:
+//! ```
+//! # use serde::Deserialize;
+//! # use validated_newtype::validated_newtype;
+//! # validated_newtype! {
+//! #     #[derive(Debug, PartialEq)]
+//! #     u32 => pub Percent
+//! #     if |n: &u32| *n <= 100;
+//! #     error "percent must be in range 0-100";
+//! #     default 0;
+//! # }
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     #[serde(deserialize_with = "Percent::deserialize_or_default")]
+//!     threshold: Percent,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{"threshold": 42}"#).unwrap();
+//! assert_eq!(*config.threshold, 42);
+//! let config: Config = serde_json::from_str(r#"{"threshold": 1337}"#).unwrap();
+//! assert_eq!(*config.threshold, 0);
+//! assert!(serde_json::from_str::<Config>(r#"{"threshold": "high"}"#).is_err());
+//! ```
+//!
+//! `transparent_repr` marks the newtype `#[repr(transparent)]`, guaranteeing the same layout
+//! and ABI as the base type, e.g. for FFI. It's opt-in to not conflict with user-provided `#[repr]`.
+//! `#[derive(Validated)]` rejects it, since the attribute has to be written on the struct directly:
+//! ```
+//! # use core::mem::{align_of, size_of};
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     transparent_repr;
+//! }
+//!
+//! assert_eq!(size_of::<Percent>(), size_of::<u32>());
+//! assert_eq!(align_of::<Percent>(), align_of::<u32>());
+//! ```
+//!
+//! `as_ref <type>` additionally implements [AsRef] to any type the base type can be referenced as:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Username
+//!     if |s: &String| !s.is_empty();
+//!     error "username must not be empty";
+//!     as_ref str;
+//! }
+//!
+//! fn greet(name: impl AsRef<str>) -> String {
+//!     format!("Hello, {}!", name.as_ref())
+//! }
+//!
+//! fn owned_len(name: impl AsRef<String>) -> usize {
+//!     name.as_ref().len()
+//! }
+//!
+//! let name = Username::new("ferris".to_string()).unwrap();
+//! assert_eq!(owned_len(&name), 6);
+//! assert_eq!(greet(name), "Hello, ferris!");
+//! ```
+//!
+//! `borrow` implements [Borrow] to the base type, and `borrow <type>` implements it to any type
+//! the base type borrows as. This allows looking up map keys without constructing a newtype.
+//! [Borrow] requires [Hash], [Eq] and [Ord] to behave identically for the newtype and the borrowed
+//! value, which holds for derived impls, since they delegate to the inner value:
+//! ```
+//! # use std::collections::HashMap;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, PartialEq, Eq, Hash)]
+//!     String => pub Email
+//!     if |s: &String| s.contains('@');
+//!     error "email must contain @";
+//!     borrow;
+//!     borrow str;
+//! }
+//!
+//! let mut users = HashMap::new();
+//! users.insert(Email::new("ferris@rust-lang.org".to_string()).unwrap(), "Ferris");
+//! assert_eq!(users.get("ferris@rust-lang.org"), Some(&"Ferris"));
+//! assert_eq!(users.get(&"ferris@rust-lang.org".to_string()), Some(&"Ferris"));
+//!
+//! validated_newtype! {
+//!     #[derive(Debug, PartialEq, Eq, Hash)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100";
+//!     borrow;
+//! }
+//!
+//! let mut labels = HashMap::new();
+//! labels.insert(Percent::new(100).unwrap(), "full");
+//! assert_eq!(labels.get(&100), Some(&"full"));
+//! assert_eq!(labels.get(&1337), None);
+//! ```
+//!
+//! By default, [Deserialize] reports validation failure with the error's [Display] output.
+//! `invalid_value "<expected>"` reports it as serde's invalid value error instead,
+//! with the rejected value and the given description of what was expected.
+//! Base type must implement [ToUnexpected] (implemented for primitives and strings):
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! # use serde_json;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     in 0..=100;
+//!     invalid_value "percent 0-100";
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     &'a str => pub Token<'a>
+//!     if |s: &&str| !s.contains(' ');
+//!     error "token must not contain spaces";
+//!     invalid_value "a token without spaces";
+//! }
+//!
+//! let err = serde_json::from_str::<Percent>("1337").unwrap_err();
+//! assert_eq!(err.to_string(), "invalid value: integer `1337`, expected percent 0-100");
+//!
+//! let err = serde_json::from_str::<Token>(r#""a b""#).unwrap_err();
+//! assert!(err.to_string().starts_with(r#"invalid value: string "a b", expected a token without spaces"#));
+//! ```
+//!
+//! Serde doesn't tell the newtype which field is being deserialized, so the message alone may be
+//! ambiguous when several fields share an error. `prefix_errors` prefixes it with the newtype name:
+//! ```
+//! # use serde::Deserialize;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     in 0..=100;
+//!     prefix_errors;
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! struct Limits {
+//!     cpu: Percent,
+//!     memory: Percent,
+//! }
+//!
+//! let err = serde_json::from_str::<Limits>(r#"{"cpu": 42, "memory": 1337}"#).unwrap_err();
+//! assert!(err.to_string().starts_with("Percent: value must be in range 0..=100"));
+//! ```
+//!
+//! Type mismatches are reported by the base type, so they mention it instead of the newtype,
+//! e.g. "expected u32". `expecting` replaces the description with the newtype name,
+//! and `expecting "<description>"` with the given description:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     in 0..=100;
+//!     expecting;
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Username
+//!     non_empty;
+//!     expecting "a username";
+//! }
+//!
+//! let err = serde_json::from_str::<Percent>(r#""42""#).unwrap_err();
+//! assert!(err.to_string().starts_with(r#"invalid type: string "42", expected Percent"#));
+//!
+//! let err = serde_json::from_str::<Username>("42").unwrap_err();
+//! assert!(err.to_string().starts_with("invalid type: integer `42`, expected a username"));
+//!
+//! // Validation errors are not affected
+//! let err = serde_json::from_str::<Percent>("1337").unwrap_err();
+//! assert!(err.to_string().starts_with("value must be in range 0..=100"));
+//! ```
+//!
+//! ## Ranges
+//! Range check is the most common predicate, so there's a shorthand for it: `in <range>`.
+//! Bounds must be literals. It works with any base type implementing [PartialOrd],
+//! and inclusive bounds are also available as `MIN` and `MAX` associated constants:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     in 0..=100
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     f64 => pub Probability
+//!     in 0.0..=1.0
+//! }
+//!
+//! assert!(Percent::new(100).is_ok());
+//! assert_eq!(Percent::new(101).unwrap_err(), "value must be in range 0..=100");
+//! assert_eq!((Percent::MIN, Percent::MAX), (0, 100));
+//! assert!(Probability::new(0.5).is_ok());
+//! assert!(Probability::new(f64::NAN).is_err());
+//! ```
+//!
+//! Inclusive ranges also generate `saturating()` constructor, which clamps the value into
+//! the range instead of failing. NaN is clamped to the lower bound:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     u32 => pub Percent
+//! #     in 0..=100
+//! # }
+//! # validated_newtype! {
+//! #     #[derive(Debug)]
+//! #     f64 => pub Probability
+//! #     in 0.0..=1.0
+//! # }
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     i8 => pub Offset
+//!     in -10..=10
+//! }
+//!
+//! assert_eq!(*Percent::saturating(42), 42);
+//! assert_eq!(*Percent::saturating(150), 100);
+//! assert_eq!(*Offset::saturating(-100), -10);
+//! assert_eq!(*Offset::saturating(100), 10);
+//! assert_eq!(*Probability::saturating(-0.5), 0.0);
+//! assert_eq!(*Probability::saturating(f64::NAN), 0.0);
+//! ```
+//!
+//! Clamped value goes through the sanitizer, same as with other constructors:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     in 0..=100;
+//!     sanitize |n: u32| n - n % 10;
+//! }
+//!
+//! assert_eq!(*Percent::saturating(47), 40);
+//! assert_eq!(*Percent::saturating(150), 100);
+//! ```
+//!
+//! Exclusive and half-open ranges are supported as well:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u8 => pub Digit
+//!     in 0..10
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u16 => pub Port
+//!     in 1024..
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     i32 => pub Negative
+//!     in ..0
+//! }
+//!
+//! assert!(Digit::new(0).is_ok());
+//! assert!(Digit::new(9).is_ok());
+//! assert_eq!(Digit::new(10).unwrap_err(), "value must be in range 0..10");
+//!
+//! assert!(Port::new(1023).is_err());
+//! assert!(Port::new(1024).is_ok());
+//! assert!(Port::new(u16::MAX).is_ok());
+//!
+//! assert!(Negative::new(i32::MIN).is_ok());
+//! assert!(Negative::new(-1).is_ok());
+//! assert_eq!(Negative::new(0).unwrap_err(), "value must be in range ..0");
+//! ```
+//!
+//! Similarly, `len <range>` checks `len()` of the value, so it works for strings and collections.
+//! Note that for strings `len()` is the length in bytes. To count characters instead, use `chars <range>`:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<u8> => pub Key
+//!     len 16..=32
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Bio
+//!     chars 1..=3
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Username
+//!     len 3..=8
+//! }
+//!
+//! assert!(Key::new(vec![0; 15]).is_err());
+//! assert!(Key::new(vec![0; 16]).is_ok());
+//! assert!(Key::new(vec![0; 32]).is_ok());
+//! assert_eq!(Key::new(vec![0; 33]).unwrap_err(), "length must be in range 16..=32");
+//!
+//! assert!(Username::new("ab".to_string()).is_err());
+//! assert!(Username::new("abc".to_string()).is_ok());
+//! assert!(Username::new("abcdefgh".to_string()).is_ok());
+//! assert!(Username::new("abcdefghi".to_string()).is_err());
+//! // `len` counts bytes: 4 characters are 8 bytes, 5 characters are 10 bytes
+//! assert!(Username::new("ññññ".to_string()).is_ok());
+//! assert!(Username::new("ñññññ".to_string()).is_err());
+//!
+//! assert!(Bio::new("".to_string()).is_err());
+//! assert!(Bio::new("ñ".to_string()).is_ok());
+//! assert!(Bio::new("ñññ".to_string()).is_ok());
+//! assert!(Bio::new("ññññ".to_string()).is_err());
+//! ```
+//!
+//! The most common length check has its own shorthand, `non_empty`. It works for any base type
+//! with `is_empty()` method:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Name
+//!     non_empty
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<u8> => pub Payload
+//!     non_empty;
+//!     as_ref [u8];
+//! }
+//!
+//! assert_eq!(*Name::new("ferris".to_string()).unwrap(), "ferris");
+//! assert_eq!(Name::new(String::new()).unwrap_err(), "value must not be empty");
+//! assert_eq!(Payload::new(vec![0]).unwrap().as_ref(), [0]);
+//! assert_eq!(Payload::new(vec![]).unwrap_err(), "value must not be empty");
+//! ```
+//!
+//! Floats have `finite` and `non_nan` shorthands, rejecting NaN and infinities or only NaN.
+//! Both accept `-0.0`:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     f64 => pub Temperature
+//!     finite
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     f64 => pub Limit
+//!     non_nan
+//! }
+//!
+//! assert_eq!(*Temperature::new(36.6).unwrap(), 36.6);
+//! assert!(Temperature::new(-0.0).is_ok());
+//! assert_eq!(Temperature::new(f64::NAN).unwrap_err(), "value must be finite");
+//! assert_eq!(Temperature::new(f64::INFINITY).unwrap_err(), "value must be finite");
+//! assert_eq!(Temperature::new(f64::NEG_INFINITY).unwrap_err(), "value must be finite");
+//!
+//! assert_eq!(*Limit::new(f64::INFINITY).unwrap(), f64::INFINITY);
+//! assert!(Limit::new(-0.0).is_ok());
+//! assert_eq!(Limit::new(f64::NAN).unwrap_err(), "value must not be NaN");
+//! ```
+//!
+//! ## Named predicates
+//! Predicates and error generators are arbitrary expressions, so paths to functions may be used
+//! instead of closures. This allows sharing validators between several newtypes:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! fn is_percent(n: &u32) -> bool {
+//!     *n <= 100
+//! }
+//!
+//! fn percent_error(n: &u32) -> String {
+//!     format!("number {} is not in range 0-100", n)
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if is_percent;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Progress
+//!     if is_percent;
+//!     else percent_error => String
+//! }
+//!
+//! assert!(Percent::new(42).is_ok());
+//! assert_eq!(Percent::new(1337).unwrap_err(), "percent must be in range 0-100");
+//! assert_eq!(Progress::new(1337).unwrap_err(), "number 1337 is not in range 0-100");
+//! ```
+//!
+//! ## Multiple predicates
+//! Several predicates may be separated by commas. Newtype is valid if all of them are satisfied,
+//! and they're checked in order:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub EvenPercent
+//!     if |n: &u32| *n <= 100, |n: &u32| *n % 2 == 0;
+//!     error "number must be even and in range 0-100"
+//! }
+//!
+//! assert!(EvenPercent::new(42).is_ok());
+//! assert!(EvenPercent::new(1338).is_err());
+//! assert!(EvenPercent::new(43).is_err());
+//! ```
+//!
+//! Alternatively, each predicate may have its own error message.
+//! The message of the first failing predicate is returned:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Password
+//!     if |s: &String| s.len() >= 8 => "password must be at least 8 characters long",
+//!        |s: &String| s.chars().any(|c| c.is_ascii_digit()) => "password must contain a digit";
+//! }
+//!
+//! assert!(Password::new("hunter22".to_string()).is_ok());
+//! assert_eq!(
+//!     Password::new("hunter2".to_string()).unwrap_err(),
+//!     "password must be at least 8 characters long",
+//! );
+//! assert_eq!(
+//!     Password::new("hunterhunter".to_string()).unwrap_err(),
+//!     "password must contain a digit",
+//! );
+//! ```
+//!
+//! ## Regular expressions
+//! With `regex` feature enabled, `matches "<regex>"` clause may be used instead of predicate for newtypes
+//! over types implementing `AsRef<str>`. The whole string must match the pattern, so there's no need
+//! to anchor it manually. The regex is compiled once, on first use, and an invalid pattern panics at
+//! that point. `regex()` returns the compiled regex, which is shared by all checks:
+//! ```
+//! # #[cfg(feature = "regex")] {
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Slug
+//!     matches r"[a-z0-9-]+"
+//! }
+//!
+//! assert!(Slug::new("hello-world".to_string()).is_ok());
+//! assert!(Slug::new("hello world".to_string()).is_err());
+//! assert!(Slug::new("hello-world!".to_string()).is_err());
+//! assert_eq!(Slug::new("".to_string()).unwrap_err(), "value must match pattern [a-z0-9-]+");
+//!
+//! assert_eq!(Slug::regex().as_str(), "^(?:[a-z0-9-]+)$");
+//! assert!(core::ptr::eq(Slug::regex(), Slug::regex()));
+//! # }
+//! ```
+//!
+//! ## Validating with [Result]
+//! If validation naturally produces an error, `validate` clause may be used instead of predicate.
+//! Closure must return `Result<(), E>`, and `E` is used as the error type.
+//! As with other error types, `E` must implement [Display] for [Deserialize] to work:
+//! ```
+//! # use core::fmt;
+//! # use validated_newtype::validated_newtype;
+//! #[derive(Debug, PartialEq)]
+//! pub enum VersionError {
+//!     NoDot,
+//!     NotANumber,
+//! }
+//!
+//! impl fmt::Display for VersionError {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         match self {
+//!             Self::NoDot => f.write_str("version must contain a dot"),
+//!             Self::NotANumber => f.write_str("version components must be numbers"),
+//!         }
+//!     }
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Version
+//!     validate |s: &String| -> Result<(), VersionError> {
+//!         let (major, minor) = s.split_once('.').ok_or(VersionError::NoDot)?;
+//!         major.parse::<u32>().map_err(|_| VersionError::NotANumber)?;
+//!         minor.parse::<u32>().map_err(|_| VersionError::NotANumber)?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! assert_eq!(*Version::new("1.42".to_string()).unwrap(), "1.42");
+//! assert_eq!(Version::new("1".to_string()).unwrap_err(), VersionError::NoDot);
+//! assert_eq!(Version::new("1.x".to_string()).unwrap_err(), VersionError::NotANumber);
+//! ```
+//!
+//! ## Validating with context
+//! Predicate may take a reference to a context as the second argument, for checks that depend on
+//! runtime configuration. `try_new_with()` and `validate_with()` accept the context explicitly.
+//! [TryFrom] and [Deserialize] have nowhere to get the context from, so they aren't generated,
+//! and newtypes can only be constructed with `try_new_with()`:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! pub struct Limits {
+//!     max_len: usize,
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Comment
+//!     if |s: &String, limits: &Limits| s.len() <= limits.max_len;
+//!     error "comment is too long"
+//! }
+//!
+//! let strict = Limits { max_len: 5 };
+//! assert_eq!(*Comment::try_new_with("hello".to_string(), &strict).unwrap(), "hello");
+//! assert_eq!(Comment::try_new_with("hello world".to_string(), &strict).unwrap_err(), "comment is too long");
+//! assert!(Comment::validate_with(&"hello world".to_string(), &strict).is_err());
+//! assert_eq!(serde_json::to_string(&Comment::try_new_with("hi".to_string(), &strict).unwrap()).unwrap(), r#""hi""#);
+//! ```
+//!
+//! Predicates with context accept the same error forms as others, e.g. [named error types](#named-error-types):
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! pub struct Limits {
+//!     max: u32,
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Quota
+//!     if |n: &u32, limits: &Limits| *n <= limits.max;
+//!     error QuotaError "quota exceeds the limit"
+//! }
+//!
+//! let limits = Limits { max: 10 };
+//! assert_eq!(*Quota::try_new_with(5, &limits).unwrap(), 5);
+//! assert_eq!(Quota::try_new_with(50, &limits).unwrap_err(), QuotaError { value: 50 });
+//! assert_eq!(QuotaError { value: 50 }.to_string(), "quota exceeds the limit");
+//! ```
+//!
+//! When there's a sensible default, `default_context` option generates [TryFrom], [Deserialize],
+//! `new()` and `validate()`, which check the value against [Default] context:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! pub struct Limits {
+//!     max_len: usize,
+//! }
+//!
+//! impl Default for Limits {
+//!     fn default() -> Self {
+//!         Self { max_len: 280 }
+//!     }
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Comment
+//!     if |s: &String, limits: &Limits| s.len() <= limits.max_len;
+//!     error "comment is too long";
+//!     default_context;
+//! }
+//!
+//! let strict = Limits { max_len: 5 };
+//! assert!(Comment::try_new_with("hello world".to_string(), &strict).is_err());
+//! assert!(Comment::new("hello world".to_string()).is_ok());
+//! assert!(Comment::try_from("a".repeat(281)).is_err());
+//! assert!(serde_json::from_str::<Comment>(&format!("{:?}", "a".repeat(281))).is_err());
+//! ```
+//!
+//! Without the option, there's no [TryFrom] impl:
+//! ```compile_fail
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! pub struct Limits {
+//!     max_len: usize,
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     String => pub Comment
+//!     if |s: &String, limits: &Limits| s.len() <= limits.max_len;
+//!     error "comment is too long"
+//! }
+//!
+//! let _ = Comment::try_from("hello".to_string());
+//! ```
+//!
+//! ## Nesting newtypes
+//! Newtypes may be used as base types of other newtypes to stack invariants. Deserialization goes
+//! through the inner newtype, so its check runs first and its errors are reported as is.
+//! `from [...]` option allows constructing the outer newtype from the innermost value directly:
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::{validated_newtype, ConversionError};
+//! validated_newtype! {
+//!     #[derive(Debug, Clone, Copy)]
+//!     u32 => pub Percent
+//!     in 0..=100
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Percent => pub SmallPercent
+//!     if |p: &Percent| **p < 10;
+//!     error "percent must be below 10";
+//!     from [u32];
+//! }
+//!
+//! let small: SmallPercent = serde_json::from_str("5").unwrap();
+//! assert_eq!(**small, 5);
+//!
+//! let err = serde_json::from_str::<SmallPercent>("50").unwrap_err();
+//! assert!(err.to_string().starts_with("percent must be below 10"));
+//! let err = serde_json::from_str::<SmallPercent>("500").unwrap_err();
+//! assert!(err.to_string().starts_with("value must be in range 0..=100"));
+//!
+//! assert_eq!(**SmallPercent::try_from(5).unwrap(), 5);
+//! assert_eq!(SmallPercent::try_from(50).unwrap_err(), ConversionError::Invalid("percent must be below 10"));
+//! assert_eq!(SmallPercent::try_from(500).unwrap_err(), ConversionError::Convert("value must be in range 0..=100"));
+//! ```
+//!
+//! ## Generic newtypes
+//! Newtype may have type parameters, which can be used in the base type and in the predicate.
+//! Bounds are written inline as trait paths joined with `+`, and every generated impl
+//! additionally requires the base type to implement the corresponding trait
+//! (e.g. `Vec<T>: Deserialize` for [Deserialize]):
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<T> => pub NonEmpty<T>
+//!     if |v: &Vec<T>| !v.is_empty();
+//!     error "vec must not be empty"
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<T> => pub Sorted<T: Ord>
+//!     if |v: &Vec<T>| v.windows(2).all(|w| w[0] <= w[1]);
+//!     error "vec must be sorted"
+//! }
+//!
+//! let names: NonEmpty<String> = serde_json::from_str(r#"["foo", "bar"]"#).unwrap();
+//! assert_eq!(names.len(), 2);
+//! assert!(serde_json::from_str::<NonEmpty<u32>>("[]").is_err());
+//!
+//! assert_eq!(*Sorted::new(vec![1, 2, 3]).unwrap(), [1, 2, 3]);
+//! assert_eq!(Sorted::new(vec!['b', 'a']).unwrap_err(), "vec must be sorted");
+//! ```
+//!
+//! More complex bounds may be given in a `where` clause, terminated with `;`:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Vec<T> => pub Names<T> where T: AsRef<str>;
+//!     if |v: &Vec<T>| v.iter().all(|name| !name.as_ref().is_empty());
+//!     error "names must not be empty"
+//! }
+//!
+//! let owned: Names<String> = serde_json::from_str(r#"["foo", "bar"]"#).unwrap();
+//! assert_eq!(owned[1], "bar");
+//!
+//! let borrowed: Names<&str> = serde_json::from_str(r#"["foo", "bar"]"#).unwrap();
+//! assert_eq!(borrowed[0], "foo");
+//!
+//! assert!(Names::new(vec!["foo", ""]).is_err());
+//! ```
+//!
+//! Lifetime parameters (listed before type parameters) are supported as well, so newtype may
+//! borrow from the input.
+//! [Deserialize] impl deserializes the borrowed base type directly, so no allocation happens
+//! (and, same as with `&str`, deserialization fails if the string can't be borrowed,
+//! e.g. when it contains escapes). Fields of such types need `#[serde(borrow)]`:
+//! ```
+//! # use serde::Deserialize;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, Clone, Copy)]
+//!     &'a str => pub Token<'a>
+//!     if |s: &&str| !s.is_empty();
+//!     error "token must not be empty"
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! struct Message<'a> {
+//!     #[serde(borrow)]
+//!     token: Token<'a>,
+//! }
+//!
+//! let input = String::from(r#"{"token": "abc"}"#);
+//! let message: Message = serde_json::from_str(&input).unwrap();
+//! assert_eq!(*message.token, "abc");
+//! assert_eq!(message.token.as_ptr(), input[11..].as_ptr());
+//! assert!(serde_json::from_str::<Message>(r#"{"token": ""}"#).is_err());
+//!
+//! // Escaped strings can't be borrowed from the input
+//! let err = serde_json::from_str::<Message>(r#"{"token": "a\nb"}"#).unwrap_err();
+//! assert!(err.to_string().contains("a borrowed string"));
+//! ```
+//!
+//! To accept escaped strings too, use `Cow<'a, str>` base type with `deserialize_borrowed` option
+//! (requires `alloc` feature), which borrows from the input when possible instead of always
+//! allocating. `deref <type>` option changes [Deref] target to any type the base type
+//! dereferences to, e.g. to `str` instead of `Cow<'a, str>`:
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use std::borrow::Cow;
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Cow<'a, str> => pub Token<'a>
+//!     if |s: &Cow<'a, str>| !s.is_empty();
+//!     error "token must not be empty";
+//!     deserialize_borrowed;
+//!     deref str;
+//! }
+//!
+//! let token: Token = serde_json::from_str(r#""abc""#).unwrap();
+//! assert!(matches!(token.as_ref(), Cow::Borrowed("abc")));
+//!
+//! let token: Token = serde_json::from_str(r#""a\nb""#).unwrap();
+//! assert!(matches!(token.as_ref(), Cow::Owned(_)));
+//!
+//! let s: &str = &token;
+//! assert_eq!(s, "a\nb");
+//! # }
+//! ```
+//!
+//! Base type must not be a bare type parameter, since generated [TryFrom] and [Into] impls
+//! would conflict with blanket impls from `core`. Named error types holding the value (`error <name>`) require
+//! a concrete base type.
+//!
+//! ## Named error types
+//! `error <name> "<message>"` generates an error struct with the same visibility as the newtype.
+//! It implements [Display] with the given message and holds the rejected value.
+//! The struct derives `Debug`, [Clone] and [PartialEq], so the base type must implement them too.
+//! Since `macro_rules!` can't create new identifiers, the name must always be given explicitly:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error PercentError "percent must be in range 0-100"
+//! }
+//!
+//! let err: PercentError = Percent::new(1337).unwrap_err();
+//! assert_eq!(err.value, 1337);
+//! assert_eq!(err.to_string(), "percent must be in range 0-100");
+//! ```
+//!
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, Clone, PartialEq)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Percent => pub Even
+//!     if |p: &Percent| **p % 2 == 0;
+//!     error EvenError "percent must be even"
+//! }
+//!
+//! let odd = Percent::new(41).unwrap();
+//! assert_eq!(Even::new(odd.clone()).unwrap_err(), EvenError { value: odd });
+//! ```
+//!
+//! ```compile_fail
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug, Clone)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error "percent must be in range 0-100"
+//! }
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     Percent => pub Even
+//!     if |p: &Percent| **p % 2 == 0;
+//!     error EvenError "percent must be even" // `Percent` doesn't implement `PartialEq`
+//! }
+//! ```
+//!
+//! If the message is omitted, error is displayed as `invalid value <value>`,
+//! which requires base type to implement [Display]:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error PercentError
+//! }
+//!
+//! let err = Percent::new(1337).unwrap_err();
+//! assert_eq!(err.value, 1337);
+//! assert_eq!(err.to_string(), "invalid value 1337");
+//! ```
+//!
+//! If the value isn't needed, `error type <name> = "<message>"` generates a unit struct instead:
+//! ```
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error type PercentError = "percent must be in range 0-100"
+//! }
+//!
+//! assert_eq!(Percent::new(1337).unwrap_err(), PercentError);
+//! assert_eq!(PercentError.to_string(), "percent must be in range 0-100");
+//! ```
+//!
+//! With `std` feature enabled, generated error types (and [FromStrError]) also implement [std::error::Error]:
+//! ```
+//! # #[cfg(feature = "std")] {
+//! # use validated_newtype::validated_newtype;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     error type PercentError = "percent must be in range 0-100"
+//! }
+//!
+//! fn parse(n: u32) -> Result<Percent, Box<dyn std::error::Error>> {
+//!     Ok(Percent::new(n)?)
+//! }
+//!
+//! assert_eq!(parse(1337).unwrap_err().to_string(), "percent must be in range 0-100");
+//!
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u8 => pub Small
+//!     if |n: &u8| *n < 10;
+//!     error type SmallError = "number must be less than 10";
+//!     from_str;
+//! }
+//!
+//! fn parse_str(s: &str) -> Result<Small, Box<dyn std::error::Error>> {
+//!     Ok(s.parse()?)
+//! }
+//!
+//! assert_eq!(parse_str("42").unwrap_err().to_string(), "number must be less than 10");
+//! assert!(parse_str("1337").unwrap_err().source().is_some());
+//! # }
+//! ```
+//!
+//! ## Dynamic error generation
+//! ```
+//! # use core::convert::TryInto;
+//! # use validated_newtype::validated_newtype;
+//! # use serde_json;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//!     if |n: &u32| *n <= 100;
+//!     else |n: &u32| format!("number {} is not in range 0-100", n) => String
+//! }
+//!
+//! // Deserialize for newtypes uses try_into internally
+//! let x: Result<Percent, _> = 1337.try_into();
+//! assert!(x.is_err());
+//! assert_eq!(x.unwrap_err(), "number 1337 is not in range 0-100");
+//! ```
+//!
+//! `String` errors need an allocator. Without `alloc`, use `&'static str` errors or
+//! [named error types](#named-error-types), which store the rejected value instead of formatting it.
+//!
+//! ## Manually implement [TryFrom]
+//! ```
+//! # use core::convert::TryFrom;
+//! # use validated_newtype::validated_newtype;
+//! # use serde_json;
+//! validated_newtype! {
+//!     #[derive(Debug)]
+//!     u32 => pub Percent
+//! }
+//!
+//! impl TryFrom<u32> for Percent {
+//!     type Error = &'static str;
+//!
+//!     fn try_from(val: u32) -> Result<Self, Self::Error> {
+//!         if val > 100 {
+//!             Err("percent must be in range 0-100")
+//!         } else {
+//!             Ok(Self(val))
+//!         }
+//!     }
+//! }
+//!
+//! let x: Percent = serde_json::from_str("42").unwrap();
+//! assert_eq!(*x, 42);
+//! let y: Result<Percent, _> = serde_json::from_str("1337");
+//! assert!(y.is_err());
+//! ```
+//!
+//! [TryFrom]: https://doc.rust-lang.org/stable/core/convert/trait.TryFrom.html
+//! [Deserialize]: https://docs.rs/serde/latest/serde/trait.Deserialize.html
+//! [Serialize]: https://docs.rs/serde/latest/serde/trait.Serialize.html
+//! [JsonSchema]: https://docs.rs/schemars/latest/schemars/trait.JsonSchema.html
+//! [BorshSerialize]: https://docs.rs/borsh/latest/borsh/ser/trait.BorshSerialize.html
+//! [BorshDeserialize]: https://docs.rs/borsh/latest/borsh/de/trait.BorshDeserialize.html
+//! [TransparentWrapper]: https://docs.rs/bytemuck/latest/bytemuck/trait.TransparentWrapper.html
+//! [sqlx::Type]: https://docs.rs/sqlx/latest/sqlx/trait.Type.html
+//! [sqlx::Encode]: https://docs.rs/sqlx/latest/sqlx/trait.Encode.html
+//! [sqlx::Decode]: https://docs.rs/sqlx/latest/sqlx/trait.Decode.html
+//! [ToSql]: https://docs.rs/diesel/latest/diesel/serialize/trait.ToSql.html
+//! [FromSql]: https://docs.rs/diesel/latest/diesel/deserialize/trait.FromSql.html
+//! [Queryable]: https://docs.rs/diesel/latest/diesel/deserialize/trait.Queryable.html
+//! [AsExpression]: https://docs.rs/diesel/latest/diesel/expression/trait.AsExpression.html
+//! [Validate]: https://docs.rs/validator/latest/validator/trait.Validate.html
+//! [ValidationErrors]: https://docs.rs/validator/latest/validator/struct.ValidationErrors.html
+//! [proptest::arbitrary::Arbitrary]: https://docs.rs/proptest/latest/proptest/arbitrary/trait.Arbitrary.html
+//! [Debug]: https://doc.rust-lang.org/stable/core/fmt/trait.Debug.html
+//! [quickcheck::Arbitrary]: https://docs.rs/quickcheck/latest/quickcheck/trait.Arbitrary.html
+//! [arbitrary::Arbitrary]: https://docs.rs/arbitrary/latest/arbitrary/trait.Arbitrary.html
+//! [Deref]: https://doc.rust-lang.org/stable/core/ops/trait.Deref.html
+//! [Into]: https://doc.rust-lang.org/stable/core/convert/trait.Into.html
+//! [FromStr]: https://doc.rust-lang.org/stable/core/str/trait.FromStr.html
+//! [Display]: https://doc.rust-lang.org/stable/core/fmt/trait.Display.html
+//! [Result]: https://doc.rust-lang.org/stable/core/result/enum.Result.html
+//! [Clone]: https://doc.rust-lang.org/stable/core/clone/trait.Clone.html
+//! [Default]: https://doc.rust-lang.org/stable/core/default/trait.Default.html
+//! [std::error::Error]: https://doc.rust-lang.org/stable/std/error/trait.Error.html
+//! [AsRef]: https://doc.rust-lang.org/stable/core/convert/trait.AsRef.html
+//! [Borrow]: https://doc.rust-lang.org/stable/core/borrow/trait.Borrow.html
+//! [Hash]: https://doc.rust-lang.org/stable/core/hash/trait.Hash.html
+//! [Eq]: https://doc.rust-lang.org/stable/core/cmp/trait.Eq.html
+//! [PartialEq]: https://doc.rust-lang.org/stable/core/cmp/trait.PartialEq.html
+//! [Ord]: https://doc.rust-lang.org/stable/core/cmp/trait.Ord.html
+//! [PartialOrd]: https://doc.rust-lang.org/stable/core/cmp/trait.PartialOrd.html
+//! [ToUnexpected]: https://docs.rs/validated_newtype/latest/validated_newtype/trait.ToUnexpected.html
+//! [ConversionError]: https://docs.rs/validated_newtype/latest/validated_newtype/enum.ConversionError.html
+//! [valid!]: https://docs.rs/validated_newtype/latest/validated_newtype/macro.valid.html
+// }}}
+
+#![no_std]
+
+#[cfg(any(
+    feature = "alloc",
+    feature = "schemars",
+    feature = "validator",
+    feature = "quickcheck"
+))]
+extern crate alloc;
+#[cfg(any(feature = "std", feature = "regex"))]
+extern crate std;
+
+use core::fmt;
+
+#[cfg(feature = "derive")]
+pub use validated_newtype_derive::Validated;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "schemars")]
+    pub use alloc::borrow::Cow;
+    #[cfg(feature = "schemars")]
+    pub use schemars;
+    #[cfg(feature = "borsh")]
+    pub use borsh;
+    #[cfg(feature = "validator")]
+    pub use validator;
+    #[cfg(feature = "schemars")]
+    pub use serde_json::Value as JsonValue;
+    #[cfg(any(
+        feature = "validator",
+        feature = "borsh",
+        feature = "sqlx",
+        feature = "diesel"
+    ))]
+    pub use alloc::string::ToString;
+    #[cfg(feature = "quickcheck")]
+    pub use alloc::boxed::Box;
+    #[cfg(all(feature = "serde", feature = "alloc"))]
+    pub use alloc::{string::String, vec::Vec};
+    #[cfg(feature = "regex")]
+    pub use regex::Regex;
+    #[cfg(feature = "regex")]
+    pub use std::sync::OnceLock;
+    #[cfg(feature = "std")]
+    pub use std::error::Error as StdError;
+    #[cfg(feature = "serde")]
+    pub use crate::expecting::ExpectingDeserializer;
+
+    /// Bit representation for hashing floats, equal for values that compare equal.
+    pub trait FloatBits {
+        fn float_bits(&self) -> u64;
+    }
+
+    impl FloatBits for f32 {
+        fn float_bits(&self) -> u64 {
+            // `-0.0 == 0.0`, so they must hash the same
+            if *self == 0.0 {
+                0
+            } else {
+                self.to_bits().into()
+            }
+        }
+    }
+
+    impl FloatBits for f64 {
+        fn float_bits(&self) -> u64 {
+            if *self == 0.0 {
+                0
+            } else {
+                self.to_bits()
+            }
+        }
+    }
+}
+
+/// Error returned by `FromStr` implementation generated with `from_str` option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromStrError<P, E> {
+    /// Base type failed to parse.
+    Parse(P),
+    /// Base type parsed successfully, but the predicate failed.
+    Invalid(E),
+}
+
+impl<P: fmt::Display, E: fmt::Display> fmt::Display for FromStrError<P, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => err.fmt(f),
+            Self::Invalid(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P, E> std::error::Error for FromStrError<P, E>
+where
+    P: std::error::Error + 'static,
+    E: fmt::Debug + fmt::Display,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Invalid(_) => None,
+        }
+    }
+}
+
+/// Error returned by `TryFrom` implementations generated with `from [...]` option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError<C, E> {
+    /// Source value failed to convert into the base type.
+    Convert(C),
+    /// Source value converted successfully, but the predicate failed.
+    Invalid(E),
+}
+
+impl<C: fmt::Display, E: fmt::Display> fmt::Display for ConversionError<C, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Convert(err) => err.fmt(f),
+            Self::Invalid(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C, E> std::error::Error for ConversionError<C, E>
+where
+    C: std::error::Error + 'static,
+    E: fmt::Debug + fmt::Display,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Convert(err) => Some(err),
+            Self::Invalid(_) => None,
+        }
+    }
+}
+
+/// Describes a rejected value for `invalid_value` option.
+#[cfg(feature = "serde")]
+pub trait ToUnexpected {
+    /// Returns serde's description of the value.
+    fn to_unexpected(&self) -> serde::de::Unexpected<'_>;
+}
+
+#[cfg(feature = "serde")]
+macro_rules! impl_to_unexpected {
+    ($variant:ident($repr:ty): $( $type:ty ),*) => {
+        $(
+            impl ToUnexpected for $type {
+                fn to_unexpected(&self) -> serde::de::Unexpected<'_> {
+                    serde::de::Unexpected::$variant(*self as $repr)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "serde")]
+impl_to_unexpected!(Unsigned(u64): u8, u16, u32, u64, usize);
+#[cfg(feature = "serde")]
+impl_to_unexpected!(Signed(i64): i8, i16, i32, i64, isize);
+#[cfg(feature = "serde")]
+impl_to_unexpected!(Float(f64): f32, f64);
+#[cfg(feature = "serde")]
+impl_to_unexpected!(Bool(bool): bool);
+#[cfg(feature = "serde")]
+impl_to_unexpected!(Char(char): char);
+
+#[cfg(feature = "serde")]
+impl ToUnexpected for str {
+    fn to_unexpected(&self) -> serde::de::Unexpected<'_> {
+        serde::de::Unexpected::Str(self)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl ToUnexpected for alloc::string::String {
+    fn to_unexpected(&self) -> serde::de::Unexpected<'_> {
+        serde::de::Unexpected::Str(self)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl ToUnexpected for alloc::borrow::Cow<'_, str> {
+    fn to_unexpected(&self) -> serde::de::Unexpected<'_> {
+        serde::de::Unexpected::Str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ToUnexpected + ?Sized> ToUnexpected for &T {
+    fn to_unexpected(&self) -> serde::de::Unexpected<'_> {
+        T::to_unexpected(self)
+    }
+}
+
+/// Deserialization borrowing from the input when possible, used by `deserialize_borrowed` option.
+///
+/// Unlike [`serde::Deserialize`] for `Cow`, which always deserializes owned data,
+/// implementations should return borrowed data if the deserializer provides it.
+#[cfg(feature = "serde")]
+pub trait DeserializeBorrowed<'de>: Sized {
+    /// Deserializes the value, borrowing from the deserializer when possible.
+    fn deserialize_borrowed<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<'de: 'a, 'a> DeserializeBorrowed<'de> for alloc::borrow::Cow<'a, str> {
+    fn deserialize_borrowed<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CowStrVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for CowStrVisitor {
+            type Value = alloc::borrow::Cow<'de, str>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(alloc::borrow::Cow::Borrowed(v))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(alloc::borrow::Cow::Owned(v.into()))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, v: alloc::string::String) -> Result<Self::Value, E> {
+                Ok(alloc::borrow::Cow::Owned(v))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
+
+/// Deserializer adapter replacing the description of the expected value, used by `expecting` option.
+#[cfg(feature = "serde")]
+mod expecting {
+    use core::fmt;
+    use serde::de::{self, Deserializer, Visitor};
+
+    pub struct ExpectingDeserializer<D> {
+        inner: D,
+        expecting: &'static str,
+    }
+
+    impl<D> ExpectingDeserializer<D> {
+        pub fn new(inner: D, expecting: &'static str) -> Self {
+            Self { inner, expecting }
+        }
+    }
+
+    struct ExpectingVisitor<V> {
+        inner: V,
+        expecting: &'static str,
+    }
+
+    macro_rules! forward_deserialize {
+        ($( $method:ident ( $( $arg:ident : $arg_type:ty ),* ) ),* $(,)?) => {
+            $(
+                fn $method<V: Visitor<'de>>(self, $( $arg: $arg_type, )* visitor: V) -> Result<V::Value, D::Error> {
+                    self.inner.$method($( $arg, )* ExpectingVisitor { inner: visitor, expecting: self.expecting })
+                }
+            )*
+        };
+    }
+
+    impl<'de, D: Deserializer<'de>> Deserializer<'de> for ExpectingDeserializer<D> {
+        type Error = D::Error;
+
+        forward_deserialize! {
+            deserialize_any(), deserialize_bool(),
+            deserialize_i8(), deserialize_i16(), deserialize_i32(), deserialize_i64(), deserialize_i128(),
+            deserialize_u8(), deserialize_u16(), deserialize_u32(), deserialize_u64(), deserialize_u128(),
+            deserialize_f32(), deserialize_f64(), deserialize_char(),
+            deserialize_str(), deserialize_string(), deserialize_bytes(), deserialize_byte_buf(),
+            deserialize_option(), deserialize_unit(), deserialize_unit_struct(name: &'static str),
+            deserialize_newtype_struct(name: &'static str), deserialize_seq(), deserialize_tuple(len: usize),
+            deserialize_tuple_struct(name: &'static str, len: usize), deserialize_map(),
+            deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+            deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+            deserialize_identifier(), deserialize_ignored_any(),
+        }
+
+        fn is_human_readable(&self) -> bool {
+            self.inner.is_human_readable()
+        }
+    }
+
+    macro_rules! forward_visit {
+        ($( $method:ident ( $( $arg:ident : $arg_type:ty ),* ) ),* $(,)?) => {
+            $(
+                fn $method<E: de::Error>(self, $( $arg: $arg_type ),*) -> Result<Self::Value, E> {
+                    self.inner.$method($( $arg ),*)
+                }
+            )*
+        };
+    }
+
+    macro_rules! forward_visit_access {
+        ($( $method:ident ( $arg:ident : $access:ident ) ),* $(,)?) => {
+            $(
+                fn $method<A: de::$access<'de>>(self, $arg: A) -> Result<Self::Value, A::Error> {
+                    self.inner.$method($arg)
+                }
+            )*
+        };
+    }
+
+    impl<'de, V: Visitor<'de>> Visitor<'de> for ExpectingVisitor<V> {
+        type Value = V::Value;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.expecting)
+        }
+
+        forward_visit! {
+            visit_bool(v: bool),
+            visit_i8(v: i8), visit_i16(v: i16), visit_i32(v: i32), visit_i64(v: i64), visit_i128(v: i128),
+            visit_u8(v: u8), visit_u16(v: u16), visit_u32(v: u32), visit_u64(v: u64), visit_u128(v: u128),
+            visit_f32(v: f32), visit_f64(v: f64), visit_char(v: char),
+            visit_str(v: &str), visit_borrowed_str(v: &'de str),
+            visit_bytes(v: &[u8]), visit_borrowed_bytes(v: &'de [u8]),
+            visit_none(), visit_unit(),
+        }
+
+        #[cfg(feature = "alloc")]
+        forward_visit! {
+            visit_string(v: alloc::string::String), visit_byte_buf(v: alloc::vec::Vec<u8>),
+        }
+
+        forward_visit_access! {
+            visit_some(deserializer: Deserializer),
+            visit_newtype_struct(deserializer: Deserializer),
+            visit_seq(seq: SeqAccess),
+            visit_map(map: MapAccess),
+            visit_enum(data: EnumAccess),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize {
+    (@scan $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $expected:tt, $source:tt, $prefix:tt, $expecting:tt;) => {
+        $crate::add_deserialize!(@emit $generics $where_clause $type, $parent, $check, $expected, $source, $prefix, $expecting);
+    };
+    (
+        @scan $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $expected:tt, $source:tt, $prefix:tt, $expecting:tt;
+        invalid_value $new_expected:literal $( $rest:tt )*
+    ) => {
+        $crate::add_deserialize!(
+            @scan $generics $where_clause $type, $parent, $check, [$new_expected], $source, $prefix, $expecting;
+            $( $rest )*
+        );
+    };
+    (
+        @scan $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $expected:tt, $source:tt, $prefix:tt, $expecting:tt;
+        deserialize_borrowed $( $rest:tt )*
+    ) => {
+        $crate::add_deserialize!(
+            @scan $generics $where_clause $type, $parent, $check, $expected,
+            [$crate::DeserializeBorrowed, deserialize_borrowed], $prefix, $expecting;
+            $( $rest )*
+        );
+    };
+    (
+        @scan $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $expected:tt, $source:tt, $prefix:tt, $expecting:tt;
+        expecting $description:literal $( $rest:tt )*
+    ) => {
+        $crate::add_deserialize!(
+            @scan $generics $where_clause $type, $parent, $check, $expected, $source, $prefix, [$description];
+            $( $rest )*
+        );
+    };
+    (
+        @scan $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $expected:tt, $source:tt, $prefix:tt, $expecting:tt;
+        expecting $( $rest:tt )*
+    ) => {
+        $crate::add_deserialize!(
+            @scan $generics $where_clause $type, $parent, $check, $expected, $source, $prefix, [stringify!($type)];
+            $( $rest )*
+        );
+    };
+    (
+        @scan $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $expected:tt, $source:tt, $prefix:tt, $expecting:tt;
+        prefix_errors $( $rest:tt )*
+    ) => {
+        $crate::add_deserialize!(
+            @scan $generics $where_clause $type, $parent, $check, $expected, $source, [prefix], $expecting;
+            $( $rest )*
+        );
+    };
+    (
+        @scan $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $expected:tt, $source:tt, $prefix:tt, $expecting:tt;
+        $skip:tt $( $rest:tt )*
+    ) => {
+        $crate::add_deserialize!(
+            @scan $generics $where_clause $type, $parent, $check, $expected, $source, $prefix, $expecting;
+            $( $rest )*
+        );
+    };
+    (
+        @emit [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $check:tt, [],
+        [$( $de_trait:ident )::+, $de_method:ident], $prefix:tt, $expecting:tt
+    ) => {
+        #[automatically_derived]
+        impl<'de, $( $gen )*> serde::Deserialize<'de> for $type
+        where
+            $parent: $( $de_trait )::+<'de>,
+            $( $where )*
+        {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                use core::convert::TryInto as _;
+                use serde::de::Error as _;
+                <$parent as $( $de_trait )::+<'de>>::$de_method(
+                    $crate::add_deserialize!(@expecting deserializer, $expecting),
+                )?
+                    .try_into()
+                    .map_err($crate::add_deserialize!(@custom $type, $prefix))
+            }
+        }
+    };
+    (
+        @emit [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [$check:expr, [$( $sanitize:expr )?]], [$expected:literal],
+        [$( $de_trait:ident )::+, $de_method:ident], [], $expecting:tt
+    ) => {
+        #[automatically_derived]
+        impl<'de, $( $gen )*> serde::Deserialize<'de> for $type
+        where
+            $parent: $( $de_trait )::+<'de> + $crate::ToUnexpected,
+            $( $where )*
+        {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                use serde::de::Error as _;
+                let val = <$parent as $( $de_trait )::+<'de>>::$de_method(
+                    $crate::add_deserialize!(@expecting deserializer, $expecting),
+                )?;
+                $( let val: $parent = ($sanitize)(val); )?
+                match $check(&val) {
+                    Ok(()) => Ok(Self(val)),
+                    Err(_) => Err(D::Error::invalid_value(
+                        $crate::ToUnexpected::to_unexpected(&val),
+                        &$expected,
+                    )),
+                }
+            }
+        }
+    };
+    (@emit $generics:tt $where_clause:tt $type:ty, $parent:ty, [], [$expected:literal], $source:tt, $prefix:tt, $expecting:tt) => {
+        compile_error!("`invalid_value` option requires a predicate");
+    };
+    (@emit $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, [$expected:literal], $source:tt, [prefix], $expecting:tt) => {
+        compile_error!("`prefix_errors` option can't be combined with `invalid_value`");
+    };
+    (@expecting $deserializer:ident, []) => {
+        $deserializer
+    };
+    (@expecting $deserializer:ident, [$description:expr]) => {
+        $crate::__private::ExpectingDeserializer::new($deserializer, $description)
+    };
+    (@custom $type:ty, []) => {
+        D::Error::custom
+    };
+    (@custom $type:ty, [prefix]) => {
+        |err| D::Error::custom(format_args!("{}: {}", stringify!($type), err))
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, [$( $check:expr )?], $sanitize:tt; $( $option:tt )*) => {
+        $crate::add_deserialize!(
+            @scan $generics $where_clause $type, $parent, [$( $check, $sanitize )?], [], [serde::Deserialize, deserialize], [], [];
+            $( $option )*
+        );
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $sanitize:tt;) => {};
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $sanitize:tt; invalid_value $expected:literal $( $rest:tt )*) => {
+        compile_error!("`invalid_value` option requires `serde` feature of validated_newtype");
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $sanitize:tt; deserialize_borrowed $( $rest:tt )*) => {
+        compile_error!("`deserialize_borrowed` option requires `serde` feature of validated_newtype");
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $sanitize:tt; prefix_errors $( $rest:tt )*) => {
+        compile_error!("`prefix_errors` option requires `serde` feature of validated_newtype");
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $sanitize:tt; expecting $( $rest:tt )*) => {
+        compile_error!("`expecting` option requires `serde` feature of validated_newtype");
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt, $sanitize:tt; $skip:tt $( $rest:tt )*) => {
+        $crate::add_deserialize!($generics $where_clause $type, $parent, $check, $sanitize; $( $rest )*);
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_serialize {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> serde::Serialize for $type
+        where
+            $parent: serde::Serialize,
+            $( $where )*
+        {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                <$parent as serde::Serialize>::serialize(&self.0, serializer)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_serialize {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {};
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize_checked {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Deserializes and validates the inner value, returning it without wrapping.
+            /// Intended for `#[serde(deserialize_with = "...")]` on fields of the base type.
+            #[allow(dead_code)]
+            pub fn deserialize_checked<'de, D>(deserializer: D) -> Result<$parent, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                Self: serde::Deserialize<'de>,
+            {
+                <Self as serde::Deserialize<'de>>::deserialize(deserializer).map(|val| val.0)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize_checked {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {};
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize_vec_checked {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Deserializes a sequence of newtypes, reporting all invalid elements at once
+            /// instead of stopping at the first one.
+            #[allow(dead_code)]
+            pub fn deserialize_vec_checked<'de, D>(
+                deserializer: D,
+            ) -> Result<$crate::__private::Vec<Self>, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                $parent: serde::Deserialize<'de>,
+                <Self as core::convert::TryFrom<$parent>>::Error: core::fmt::Display,
+            {
+                use core::fmt::Write as _;
+                use serde::de::Error as _;
+                let raw = <$crate::__private::Vec<$parent> as serde::Deserialize<'de>>::deserialize(deserializer)?;
+                let mut valid = $crate::__private::Vec::with_capacity(raw.len());
+                let mut errors = $crate::__private::String::new();
+                for (idx, val) in raw.into_iter().enumerate() {
+                    match <Self as core::convert::TryFrom<$parent>>::try_from(val) {
+                        Ok(val) => valid.push(val),
+                        Err(err) => {
+                            if !errors.is_empty() {
+                                errors.push_str(", ");
+                            }
+                            let _ = write!(errors, "[{}]: {}", idx, err);
+                        }
+                    }
+                }
+                if errors.is_empty() {
+                    Ok(valid)
+                } else {
+                    Err(D::Error::custom(errors))
+                }
+            }
+        }
+    };
+}
+
+#[cfg(not(all(feature = "serde", feature = "alloc")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize_vec_checked {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {};
+}
+
+#[cfg(feature = "schemars")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_json_schema {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [$( $bounds:tt )*]) => {
+        #[automatically_derived]
+        impl<$( $gen )*> $crate::__private::schemars::JsonSchema for $type
+        where
+            $parent: $crate::__private::schemars::JsonSchema,
+            $( $where )*
+        {
+            fn inline_schema() -> bool {
+                <$parent as $crate::__private::schemars::JsonSchema>::inline_schema()
+            }
+
+            fn schema_name() -> $crate::__private::Cow<'static, str> {
+                <$parent as $crate::__private::schemars::JsonSchema>::schema_name()
+            }
+
+            fn schema_id() -> $crate::__private::Cow<'static, str> {
+                <$parent as $crate::__private::schemars::JsonSchema>::schema_id()
+            }
+
+            fn json_schema(
+                generator: &mut $crate::__private::schemars::SchemaGenerator,
+            ) -> $crate::__private::schemars::Schema {
+                #[allow(unused_mut)]
+                let mut schema = <$parent as $crate::__private::schemars::JsonSchema>::json_schema(generator);
+                $crate::add_json_schema!(@bounds schema, $parent, $( $bounds )*);
+                schema
+            }
+        }
+    };
+    (@bounds $schema:ident, $parent:ty, in, $( $min:literal )? ..= $max:literal) => {
+        $( $crate::add_json_schema!(@insert $schema, "minimum", $parent, $min); )?
+        $crate::add_json_schema!(@insert $schema, "maximum", $parent, $max);
+    };
+    (@bounds $schema:ident, $parent:ty, in, $( $min:literal )? .. $( $max:literal )?) => {
+        $( $crate::add_json_schema!(@insert $schema, "minimum", $parent, $min); )?
+        $( $crate::add_json_schema!(@insert $schema, "exclusiveMaximum", $parent, $max); )?
+    };
+    (@bounds $schema:ident, $parent:ty, chars, $( $min:literal )? ..= $max:literal) => {
+        $( $crate::add_json_schema!(@insert $schema, "minLength", usize, $min); )?
+        $crate::add_json_schema!(@insert $schema, "maxLength", usize, $max);
+    };
+    (@bounds $schema:ident, $parent:ty, chars, $( $min:literal )? .. $( $max:literal )?) => {
+        $( $crate::add_json_schema!(@insert $schema, "minLength", usize, $min); )?
+        $( $crate::add_json_schema!(@insert $schema, "maxLength", usize, $max - 1); )?
+    };
+    (@bounds $schema:ident, $parent:ty, $( $bounds:tt )*) => {};
+    (@insert $schema:ident, $key:literal, $bound_type:ty, $bound:expr) => {
+        let bound: $bound_type = $bound;
+        $schema.insert($key.into(), $crate::__private::JsonValue::from(bound));
+    };
+}
+
+#[cfg(not(feature = "schemars"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_json_schema {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt) => {
+        compile_error!("`json_schema` option requires `schemars` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "borsh")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_borsh {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> $crate::__private::borsh::BorshSerialize for $type
+        where
+            $parent: $crate::__private::borsh::BorshSerialize,
+            $( $where )*
+        {
+            fn serialize<W: $crate::__private::borsh::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> $crate::__private::borsh::io::Result<()> {
+                <$parent as $crate::__private::borsh::BorshSerialize>::serialize(&self.0, writer)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> $crate::__private::borsh::BorshDeserialize for $type
+        where
+            $parent: $crate::__private::borsh::BorshDeserialize,
+            <Self as core::convert::TryFrom<$parent>>::Error: core::fmt::Display,
+            $( $where )*
+        {
+            fn deserialize_reader<R: $crate::__private::borsh::io::Read>(
+                reader: &mut R,
+            ) -> $crate::__private::borsh::io::Result<Self> {
+                let val = <$parent as $crate::__private::borsh::BorshDeserialize>::deserialize_reader(reader)?;
+                <Self as core::convert::TryFrom<$parent>>::try_from(val).map_err(|err| {
+                    $crate::__private::borsh::io::Error::new(
+                        $crate::__private::borsh::io::ErrorKind::InvalidData,
+                        $crate::__private::ToString::to_string(&err),
+                    )
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "borsh"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_borsh {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty) => {
+        compile_error!("`borsh` option requires `borsh` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "sqlx")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_sqlx {
+    (@emit [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )* DB> sqlx::Type<DB> for $type
+        where
+            DB: sqlx::Database,
+            $parent: sqlx::Type<DB>,
+            $( $where )*
+        {
+            fn type_info() -> DB::TypeInfo {
+                <$parent as sqlx::Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <$parent as sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+
+        #[automatically_derived]
+        impl<'q, $( $gen )* DB> sqlx::Encode<'q, DB> for $type
+        where
+            DB: sqlx::Database,
+            $parent: sqlx::Encode<'q, DB>,
+            $( $where )*
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut DB::ArgumentBuffer<'q>,
+            ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+                <$parent as sqlx::Encode<'q, DB>>::encode_by_ref(&self.0, buf)
+            }
+
+            fn produces(&self) -> Option<DB::TypeInfo> {
+                <$parent as sqlx::Encode<'q, DB>>::produces(&self.0)
+            }
+
+            fn size_hint(&self) -> usize {
+                <$parent as sqlx::Encode<'q, DB>>::size_hint(&self.0)
+            }
+        }
+
+        #[automatically_derived]
+        impl<'r, $( $gen )* DB> sqlx::Decode<'r, DB> for $type
+        where
+            DB: sqlx::Database,
+            $parent: sqlx::Decode<'r, DB>,
+            <Self as core::convert::TryFrom<$parent>>::Error: core::fmt::Display,
+            $( $where )*
+        {
+            fn decode(value: DB::ValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+                let val = <$parent as sqlx::Decode<'r, DB>>::decode(value)?;
+                <Self as core::convert::TryFrom<$parent>>::try_from(val)
+                    .map_err(|err| $crate::__private::ToString::to_string(&err).into())
+            }
+        }
+    };
+    ([] $where_clause:tt $type:ty, $parent:ty) => {
+        $crate::add_sqlx!(@emit [] $where_clause $type, $parent);
+    };
+    ([$( $gen:tt )+] $where_clause:tt $type:ty, $parent:ty) => {
+        $crate::add_sqlx!(@emit [$( $gen )+ ,] $where_clause $type, $parent);
+    };
+}
+
+#[cfg(not(feature = "sqlx"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_sqlx {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty) => {
+        compile_error!("`sqlx` option requires `sqlx` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "diesel")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_diesel {
+    (@emit [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $sql_type:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )* DB> diesel::serialize::ToSql<$sql_type, DB> for $type
+        where
+            DB: diesel::backend::Backend,
+            $parent: diesel::serialize::ToSql<$sql_type, DB>,
+            Self: core::fmt::Debug,
+            $( $where )*
+        {
+            fn to_sql<'b>(&'b self, out: &mut diesel::serialize::Output<'b, '_, DB>) -> diesel::serialize::Result {
+                <$parent as diesel::serialize::ToSql<$sql_type, DB>>::to_sql(&self.0, out)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )* DB> diesel::deserialize::FromSql<$sql_type, DB> for $type
+        where
+            DB: diesel::backend::Backend,
+            $parent: diesel::deserialize::FromSql<$sql_type, DB>,
+            <Self as core::convert::TryFrom<$parent>>::Error: core::fmt::Display,
+            $( $where )*
+        {
+            fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+                let val = <$parent as diesel::deserialize::FromSql<$sql_type, DB>>::from_sql(bytes)?;
+                <Self as core::convert::TryFrom<$parent>>::try_from(val)
+                    .map_err(|err| $crate::__private::ToString::to_string(&err).into())
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )* DB> diesel::deserialize::Queryable<$sql_type, DB> for $type
+        where
+            DB: diesel::backend::Backend,
+            Self: diesel::deserialize::FromSql<$sql_type, DB>,
+            $( $where )*
+        {
+            type Row = Self;
+
+            fn build(row: Self) -> diesel::deserialize::Result<Self> {
+                Ok(row)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> diesel::expression::AsExpression<$sql_type> for $type
+        where
+            $parent: diesel::expression::AsExpression<$sql_type>,
+            $( $where )*
+        {
+            type Expression = <$parent as diesel::expression::AsExpression<$sql_type>>::Expression;
+
+            fn as_expression(self) -> Self::Expression {
+                <$parent as diesel::expression::AsExpression<$sql_type>>::as_expression(self.0)
+            }
+        }
+
+        #[automatically_derived]
+        impl<'expr, $( $gen )*> diesel::expression::AsExpression<$sql_type> for &'expr $type
+        where
+            &'expr $parent: diesel::expression::AsExpression<$sql_type>,
+            $( $where )*
+        {
+            type Expression = <&'expr $parent as diesel::expression::AsExpression<$sql_type>>::Expression;
+
+            fn as_expression(self) -> Self::Expression {
+                <&'expr $parent as diesel::expression::AsExpression<$sql_type>>::as_expression(&self.0)
+            }
+        }
+    };
+    ([] $where_clause:tt $type:ty, $parent:ty, $sql_type:ty) => {
+        $crate::add_diesel!(@emit [] $where_clause $type, $parent, $sql_type);
+    };
+    ([$( $gen:tt )+] $where_clause:tt $type:ty, $parent:ty, $sql_type:ty) => {
+        $crate::add_diesel!(@emit [$( $gen )+ ,] $where_clause $type, $parent, $sql_type);
+    };
+}
+
+#[cfg(not(feature = "diesel"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_diesel {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $sql_type:ty) => {
+        compile_error!("`diesel` option requires `diesel` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "miniserde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_miniserde {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> miniserde::Serialize for $type
+        where
+            $parent: miniserde::Serialize,
+            $( $where )*
+        {
+            fn begin(&self) -> miniserde::ser::Fragment<'_> {
+                <$parent as miniserde::Serialize>::begin(&self.0)
+            }
+        }
+
+        const _: () = {
+            miniserde::make_place!(Place);
+
+            impl<$( $gen )*> Place<$type>
+            where
+                $parent: miniserde::Deserialize,
+                $( $where )*
+            {
+                fn visit<F>(&mut self, f: F) -> miniserde::Result<()>
+                where
+                    F: FnOnce(&mut dyn miniserde::de::Visitor) -> miniserde::Result<()>,
+                {
+                    let mut val = None;
+                    f(<$parent as miniserde::Deserialize>::begin(&mut val))?;
+                    let val = val.ok_or(miniserde::Error)?;
+                    let val = <$type as core::convert::TryFrom<$parent>>::try_from(val)
+                        .map_err(|_| miniserde::Error)?;
+                    self.out = Some(val);
+                    Ok(())
+                }
+            }
+
+            // Only scalar base types are supported, since nested visitors would borrow the inner value
+            impl<$( $gen )*> miniserde::de::Visitor for Place<$type>
+            where
+                $parent: miniserde::Deserialize,
+                $( $where )*
+            {
+                fn null(&mut self) -> miniserde::Result<()> {
+                    self.visit(|visitor| visitor.null())
+                }
+
+                fn boolean(&mut self, b: bool) -> miniserde::Result<()> {
+                    self.visit(|visitor| visitor.boolean(b))
+                }
+
+                fn string(&mut self, s: &str) -> miniserde::Result<()> {
+                    self.visit(|visitor| visitor.string(s))
+                }
+
+                fn negative(&mut self, n: i64) -> miniserde::Result<()> {
+                    self.visit(|visitor| visitor.negative(n))
+                }
+
+                fn nonnegative(&mut self, n: u64) -> miniserde::Result<()> {
+                    self.visit(|visitor| visitor.nonnegative(n))
+                }
+
+                fn float(&mut self, n: f64) -> miniserde::Result<()> {
+                    self.visit(|visitor| visitor.float(n))
+                }
+            }
+
+            #[automatically_derived]
+            impl<$( $gen )*> miniserde::Deserialize for $type
+            where
+                $parent: miniserde::Deserialize,
+                $( $where )*
+            {
+                fn begin(out: &mut Option<Self>) -> &mut dyn miniserde::de::Visitor {
+                    Place::new(out)
+                }
+            }
+        };
+    };
+}
+
+#[cfg(not(feature = "miniserde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_miniserde {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty) => {
+        compile_error!("`miniserde` option requires `miniserde` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "bytemuck")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_bytemuck {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        // SAFETY: `bytemuck` option makes the newtype `#[repr(transparent)]` over its only field,
+        // and `#[derive(Validated)]` requires the attribute to be present
+        #[automatically_derived]
+        unsafe impl<$( $gen )*> bytemuck::TransparentWrapper<$parent> for $type
+        where
+            $( $where )*
+        {
+        }
+    };
+}
+
+#[cfg(not(feature = "bytemuck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_bytemuck {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty) => {
+        compile_error!("`bytemuck` option requires `bytemuck` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "validator")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_validator {
+    // `validate_custom()` needs predicate, and is only generated with `validator` option
+    (@custom $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt;) => {};
+    (@custom $generics:tt $where_clause:tt $type:ty, $parent:ty, []; $( $rest:tt )*) => {};
+    (@custom $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt; validator $( $rest:tt )*) => {
+        $crate::add_validator!($generics $where_clause $type, $parent);
+    };
+    (@custom $generics:tt $where_clause:tt $type:ty, $parent:ty, $check:tt; $skip:tt $( $rest:tt )*) => {
+        $crate::add_validator!(@custom $generics $where_clause $type, $parent, $check; $( $rest )*);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> $crate::__private::validator::Validate for $type
+        where
+            $( $where )*
+        {
+            fn validate(&self) -> Result<(), $crate::__private::validator::ValidationErrors> {
+                Ok(())
+            }
+        }
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Checks the predicate, reporting failure as `validator::ValidationError`.
+            /// Usable as `#[validate(custom(function = "..."))]` on fields of base type.
+            /// Accepts both values and references, since `validator` passes `Copy` fields by value.
+            #[allow(dead_code)]
+            pub fn validate_custom<Borrowed: core::borrow::Borrow<$parent>>(
+                val: Borrowed,
+            ) -> Result<(), $crate::__private::validator::ValidationError> {
+                Self::validate(val.borrow()).map_err(|err| {
+                    $crate::__private::validator::ValidationError::new(stringify!($type))
+                        .with_message($crate::__private::ToString::to_string(&err).into())
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "validator"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_validator {
+    (@custom $( $tokens:tt )*) => {};
+    ($generics:tt $where_clause:tt $type:ty) => {
+        compile_error!("`validator` option requires `validator` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "regex")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! regex_predicate {
+    ($parent:ty, $pattern:literal) => {
+        |val: &$parent| Self::regex().is_match(core::convert::AsRef::<str>::as_ref(val))
+    };
+}
+
+#[cfg(feature = "regex")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_regex {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $pattern:literal) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Regex the value must match, compiled once on first use.
+            #[allow(dead_code)]
+            pub fn regex() -> &'static $crate::__private::Regex {
+                static REGEX: $crate::__private::OnceLock<$crate::__private::Regex> =
+                    $crate::__private::OnceLock::new();
+                REGEX.get_or_init(|| {
+                    $crate::__private::Regex::new(concat!("^(?:", $pattern, ")$"))
+                        .expect(concat!("invalid regex: ", $pattern))
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "regex"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! regex_predicate {
+    ($parent:ty, $pattern:literal) => {
+        compile_error!("`matches` requires `regex` feature of validated_newtype")
+    };
+}
+
+#[cfg(not(feature = "regex"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_regex {
+    ($generics:tt $where_clause:tt $type:ty, $pattern:literal) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_validate {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $check:expr, $error_type:ty, []) => {
+        $crate::add_validate!(@emit [$( $gen )*] [$( $where )*] $type, $parent, $error_type, |val: &$parent| $check(val));
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $check:expr, $error_type:ty, [$sanitize:expr]) => {
+        $crate::add_validate!(
+            @emit [$( $gen )*] [$( $where )*] $type, $parent, $error_type,
+            // Sanitizer takes the value by value, so it has to run on a copy
+            |val: &$parent| $check(&($sanitize)(core::clone::Clone::clone(val)))
+        );
+    };
+    (@emit [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $error_type:ty, $check:expr) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Checks the predicate without constructing the newtype.
+            #[allow(dead_code)]
+            pub fn validate(val: &$parent) -> Result<(), $error_type> {
+                $check(val)
+            }
+
+            /// Checks whether the predicate holds for the value.
+            #[allow(dead_code)]
+            pub fn is_valid(val: &$parent) -> bool {
+                Self::validate(val).is_ok()
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_try_from {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $check:expr, $error_type:ty, [$( $sanitize:expr )?]) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::convert::TryFrom<$parent> for $type
+        where
+            $( $where )*
+        {
+            type Error = $error_type;
+
+            fn try_from(val: $parent) -> Result<Self, $error_type> {
+                $( let val: $parent = ($sanitize)(val); )?
+                $check(&val)?;
+                Ok(Self(val))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_std_error {
+    ($error_name:ident) => {
+        #[automatically_derived]
+        impl $crate::__private::StdError for $error_name {}
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_std_error {
+    ($error_name:ident) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_error_type {
+    ($vis:vis $error_name:ident, $type:ident, $parent:ty, $message:literal) => {
+        #[doc = concat!("Error returned when [`", stringify!($type), "`] predicate is not satisfied.")]
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $error_name {
+            /// Value that failed validation.
+            pub value: $parent,
+        }
+
+        $crate::add_error_type!(@display $error_name, $message);
+    };
+    ($vis:vis $error_name:ident, $type:ident, $parent:ty) => {
+        #[doc = concat!("Error returned when [`", stringify!($type), "`] predicate is not satisfied.")]
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $error_name {
+            /// Value that failed validation.
+            pub value: $parent,
+        }
+
+        #[automatically_derived]
+        impl core::fmt::Display for $error_name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "invalid value {}", self.value)
+            }
+        }
+
+        $crate::add_std_error!($error_name);
+    };
+    ($vis:vis $error_name:ident, $type:ident, $message:literal) => {
+        #[doc = concat!("Error returned when [`", stringify!($type), "`] predicate is not satisfied.")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        $vis struct $error_name;
+
+        $crate::add_error_type!(@display $error_name, $message);
+    };
+    (@display $error_name:ident, $message:literal) => {
+        #[automatically_derived]
+        impl core::fmt::Display for $error_name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str($message)
+            }
+        }
+
+        $crate::add_std_error!($error_name);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_new {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $error_type:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Creates a new instance, checking the predicate. Same as `TryFrom::try_from`.
+            #[allow(dead_code)]
+            pub fn try_new(val: $parent) -> Result<Self, $error_type> {
+                <Self as core::convert::TryFrom<$parent>>::try_from(val)
+            }
+
+            /// Alias for `try_new()`.
+            #[allow(dead_code)]
+            pub fn new(val: $parent) -> Result<Self, $error_type> {
+                Self::try_new(val)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_new_with {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $ctx_type:ty, $predicate:expr, $error:expr => $error_type:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Checks the predicate against the given context without constructing the newtype.
+            #[allow(dead_code)]
+            pub fn validate_with(val: &$parent, ctx: &$ctx_type) -> Result<(), $error_type> {
+                if $predicate(val, ctx) {
+                    Ok(())
+                } else {
+                    Err($error(val).into())
+                }
+            }
+
+            /// Creates a new instance, checking the predicate against the given context.
+            #[allow(dead_code)]
+            pub fn try_new_with(val: $parent, ctx: &$ctx_type) -> Result<Self, $error_type> {
+                Self::validate_with(&val, ctx)?;
+                Ok(Self(val))
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_into_parent {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::convert::From<$type> for $parent
+        where
+            $( $where )*
+        {
+            fn from(val: $type) -> $parent {
+                val.0
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_try_from_ref {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<'r, $( $gen )*> core::convert::TryFrom<&'r $parent> for $type
+        where
+            $parent: Clone,
+            $( $where )*
+        {
+            type Error = <Self as core::convert::TryFrom<$parent>>::Error;
+
+            fn try_from(val: &'r $parent) -> Result<Self, Self::Error> {
+                <Self as core::convert::TryFrom<$parent>>::try_from(Clone::clone(val))
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_map_checked {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $error_type:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Maps the inner value with `f`, checking the predicate on the result.
+            #[allow(dead_code)]
+            pub fn map_checked<F: FnOnce($parent) -> $parent>(self, f: F) -> Result<Self, $error_type> {
+                <Self as core::convert::TryFrom<$parent>>::try_from(f(self.0))
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_modify {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $parent: Clone,
+            $( $where )*
+        {
+            /// Applies `f` to a copy of the inner value and replaces the inner value with it
+            /// if the predicate still holds. On failure, the newtype is left unchanged.
+            #[allow(dead_code)]
+            pub fn modify<F: FnOnce(&mut $parent)>(
+                &mut self,
+                f: F,
+            ) -> Result<(), <Self as core::convert::TryFrom<$parent>>::Error> {
+                let mut val = Clone::clone(&self.0);
+                f(&mut val);
+                *self = <Self as core::convert::TryFrom<$parent>>::try_from(val)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_new_const {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $predicate:path) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Creates a new instance in const context, returning `None` if the predicate fails.
+            #[allow(dead_code)]
+            pub const fn new_const(val: $parent) -> Option<Self> {
+                if $predicate(&val) {
+                    Some(Self(val))
+                } else {
+                    None
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_new_unchecked {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Creates a new instance without checking the predicate.
+            ///
+            /// # Safety
+            /// Caller must guarantee that `val` satisfies the predicate.
+            #[allow(dead_code)]
+            pub const unsafe fn new_unchecked(val: $parent) -> Self {
+                Self(val)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! range_measure {
+    (in, $val:ident) => {
+        *$val
+    };
+    (len, $val:ident) => {
+        $val.len()
+    };
+    (chars, $val:ident) => {
+        $val.chars().count()
+    };
+    ($measure:ident, $val:ident) => {
+        compile_error!(concat!("unknown validated_newtype clause: ", stringify!($measure)))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! in_range {
+    ($value:expr, $( $min:literal )? ..= $max:literal) => {{
+        let value = &$value;
+        true $( && *value >= $min )? && *value <= $max
+    }};
+    ($value:expr, $( $min:literal )? .. $( $max:literal )?) => {{
+        let value = &$value;
+        true $( && *value >= $min )? $( && *value < $max )?
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! range_message {
+    (in, $( $bound:tt )*) => {
+        concat!("value must be in range ", $( stringify!($bound) ),*)
+    };
+    ($measure:ident, $( $bound:tt )*) => {
+        concat!("length must be in range ", $( stringify!($bound) ),*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_range_bounds {
+    (in, [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [$( $min:literal )?], [$max:literal]) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Clamps the value into the allowed range, so it can't fail.
+            /// Panics if `sanitize` option moves the clamped value out of the range.
+            #[allow(dead_code, clippy::neg_cmp_op_on_partial_ord)]
+            pub fn saturating(mut val: $parent) -> Self {
+                // Written with negations to also clamp NaN
+                $(
+                    if !(val >= $min) {
+                        val = $min;
+                    }
+                )?
+                if !(val <= $max) {
+                    val = $max;
+                }
+                // Goes through `TryFrom`, so that the sanitizer runs as with other constructors
+                match <Self as core::convert::TryFrom<$parent>>::try_from(val) {
+                    Ok(val) => val,
+                    Err(_) => panic!("sanitized value is out of range"),
+                }
+            }
+        }
+
+        $crate::add_range_bounds!(@consts [$( $gen )*] [$( $where )*] $type, $parent, [$( $min )?], [$max]);
+    };
+    (in, $generics:tt $where_clause:tt $type:ty, $parent:ty, $min:tt, []) => {
+        $crate::add_range_bounds!(@consts $generics $where_clause $type, $parent, $min, []);
+    };
+    (@consts [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [$( $min:literal )?], [$( $max:literal )?]) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            $(
+                /// Minimum allowed value.
+                #[allow(dead_code)]
+                pub const MIN: $parent = $min;
+            )?
+            $(
+                /// Maximum allowed value.
+                #[allow(dead_code)]
+                pub const MAX: $parent = $max;
+            )?
+        }
+    };
+    ($measure:ident, [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [$( $min:literal )?], [$( $max:literal )?]) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deref {
+    (@emit [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $target:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::ops::Deref for $type
+        where
+            $( $where )*
+        {
+            type Target = $target;
+
+            fn deref(&self) -> &$target {
+                &self.0
+            }
+        }
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty;) => {
+        $crate::add_deref!(@emit $generics $where_clause $type, $parent, $parent);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty; deref $target:ty $(; $( $rest:tt )*)?) => {
+        $crate::add_deref!(@emit $generics $where_clause $type, $parent, $target);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty; no_deref $( $rest:tt )*) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Returns a reference to the inner value.
+            #[allow(dead_code)]
+            pub fn get(&self) -> &$parent {
+                &self.0
+            }
+        }
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty; $skip:tt $( $rest:tt )*) => {
+        $crate::add_deref!($generics $where_clause $type, $parent; $( $rest )*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_as_ref {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::convert::AsRef<$parent> for $type
+        where
+            $( $where )*
+        {
+            fn as_ref(&self) -> &$parent {
+                &self.0
+            }
+        }
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $target:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::convert::AsRef<$target> for $type
+        where
+            $parent: core::convert::AsRef<$target>,
+            $( $where )*
+        {
+            fn as_ref(&self) -> &$target {
+                <$parent as core::convert::AsRef<$target>>::as_ref(&self.0)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_into_inner {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Consumes the newtype, returning the inner value.
+            #[allow(dead_code)]
+            pub fn into_inner(self) -> $parent {
+                self.0
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_from_str {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::str::FromStr for $type
+        where
+            $parent: core::str::FromStr,
+            $( $where )*
+        {
+            type Err = $crate::FromStrError<
+                <$parent as core::str::FromStr>::Err,
+                <$type as core::convert::TryFrom<$parent>>::Error,
+            >;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                use core::convert::TryFrom as _;
+                let val = s.parse::<$parent>().map_err($crate::FromStrError::Parse)?;
+                Self::try_from(val).map_err($crate::FromStrError::Invalid)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_try_from_sources {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty;) => {};
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty; & str $(, $( $rest:tt )*)?) => {
+        #[automatically_derived]
+        impl<'s, $( $gen )*> core::convert::TryFrom<&'s str> for $type
+        where
+            $parent: core::str::FromStr,
+            $( $where )*
+        {
+            type Error = $crate::FromStrError<
+                <$parent as core::str::FromStr>::Err,
+                <$type as core::convert::TryFrom<$parent>>::Error,
+            >;
+
+            fn try_from(s: &'s str) -> Result<Self, Self::Error> {
+                let val = s.parse::<$parent>().map_err($crate::FromStrError::Parse)?;
+                <Self as core::convert::TryFrom<$parent>>::try_from(val).map_err($crate::FromStrError::Invalid)
+            }
+        }
+
+        $crate::add_try_from_sources!([$( $gen )*] [$( $where )*] $type, $parent; $( $( $rest )* )?);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty; $source:ty $(, $( $rest:tt )*)?) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::convert::TryFrom<$source> for $type
+        where
+            $parent: core::convert::TryFrom<$source>,
+            $( $where )*
+        {
+            type Error = $crate::ConversionError<
+                <$parent as core::convert::TryFrom<$source>>::Error,
+                <$type as core::convert::TryFrom<$parent>>::Error,
+            >;
+
+            fn try_from(val: $source) -> Result<Self, Self::Error> {
+                let val = <$parent as core::convert::TryFrom<$source>>::try_from(val)
+                    .map_err($crate::ConversionError::Convert)?;
+                <Self as core::convert::TryFrom<$parent>>::try_from(val).map_err($crate::ConversionError::Invalid)
+            }
+        }
+
+        $crate::add_try_from_sources!([$( $gen )*] [$( $where )*] $type, $parent; $( $( $rest )* )?);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_try_from_newtypes {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty;) => {};
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty; $source:ty $(, $( $rest:tt )*)?) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::convert::TryFrom<$source> for $type
+        where
+            $parent: core::convert::From<$source>,
+            $( $where )*
+        {
+            type Error = <$type as core::convert::TryFrom<$parent>>::Error;
+
+            fn try_from(val: $source) -> Result<Self, Self::Error> {
+                <Self as core::convert::TryFrom<$parent>>::try_from(<$parent>::from(val))
+            }
+        }
+
+        $crate::add_try_from_newtypes!([$( $gen )*] [$( $where )*] $type, $parent; $( $( $rest )* )?);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_display {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::fmt::Display for $type
+        where
+            $parent: core::fmt::Display,
+            $( $where )*
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                <$parent as core::fmt::Display>::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_partial_eq_parent {
+    // `partial_ord` given later generates the same impls
+    (@unless_ord $generics:tt $where_clause:tt $type:ty, $parent:ty;) => {
+        $crate::add_partial_eq_parent!($generics $where_clause $type, $parent);
+    };
+    (@unless_ord $generics:tt $where_clause:tt $type:ty, $parent:ty; partial_ord $( $rest:tt )*) => {};
+    (@unless_ord $generics:tt $where_clause:tt $type:ty, $parent:ty; $skip:tt $( $rest:tt )*) => {
+        $crate::add_partial_eq_parent!(@unless_ord $generics $where_clause $type, $parent; $( $rest )*);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::PartialEq<$parent> for $type
+        where
+            $parent: core::cmp::PartialEq,
+            $( $where )*
+        {
+            fn eq(&self, other: &$parent) -> bool {
+                self.0 == *other
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::PartialEq<$type> for $parent
+        where
+            $parent: core::cmp::PartialEq,
+            $( $where )*
+        {
+            fn eq(&self, other: &$type) -> bool {
+                *self == other.0
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_partial_ord_parent {
+    (@scan $generics:tt $where_clause:tt $type:ty, $parent:ty;) => {
+        $crate::add_partial_eq_parent!($generics $where_clause $type, $parent);
+        $crate::add_partial_ord_parent!($generics $where_clause $type, $parent);
+    };
+    (@scan $generics:tt $where_clause:tt $type:ty, $parent:ty; partial_eq $( $rest:tt )*) => {
+        // [PartialEq] impls are generated by `partial_eq` given later
+        $crate::add_partial_ord_parent!($generics $where_clause $type, $parent);
+    };
+    (@scan $generics:tt $where_clause:tt $type:ty, $parent:ty; $skip:tt $( $rest:tt )*) => {
+        $crate::add_partial_ord_parent!(@scan $generics $where_clause $type, $parent; $( $rest )*);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::PartialOrd<$parent> for $type
+        where
+            $parent: core::cmp::PartialOrd,
+            $( $where )*
+        {
+            fn partial_cmp(&self, other: &$parent) -> Option<core::cmp::Ordering> {
+                <$parent as core::cmp::PartialOrd>::partial_cmp(&self.0, other)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::PartialOrd<$type> for $parent
+        where
+            $parent: core::cmp::PartialOrd,
+            $( $where )*
+        {
+            fn partial_cmp(&self, other: &$type) -> Option<core::cmp::Ordering> {
+                <$parent as core::cmp::PartialOrd>::partial_cmp(self, &other.0)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_total_order {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::PartialEq for $type
+        where
+            $parent: core::cmp::PartialEq,
+            $( $where )*
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::Eq for $type
+        where
+            $parent: core::cmp::PartialEq,
+            $( $where )*
+        {
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::PartialOrd for $type
+        where
+            $parent: core::cmp::PartialOrd,
+            $( $where )*
+        {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::Ord for $type
+        where
+            $parent: core::cmp::PartialOrd,
+            $( $where )*
+        {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                // `finite` and `non_nan` predicates rule out incomparable values
+                <$parent as core::cmp::PartialOrd>::partial_cmp(&self.0, &other.0)
+                    .unwrap_or(core::cmp::Ordering::Equal)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> core::hash::Hash for $type
+        where
+            $parent: $crate::__private::FloatBits,
+            $( $where )*
+        {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                state.write_u64($crate::__private::FloatBits::float_bits(&self.0));
+            }
+        }
+    };
+}
+
+/// Takes `total_order` option out of the options of a newtype with `finite` or `non_nan` predicate,
+/// which rule out NaN, and implements it directly. Only whole options are matched.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nan_free_options {
+    (@inside $args:tt [$( $flag:ident )?] [$( $done:tt )*]) => {
+        $crate::validated_newtype! { $( $done )* }
+        $( $crate::__nan_free_options!(@$flag $args); )?
+    };
+    (@inside $args:tt $flag:tt [$( $done:tt )*] ; $( $rest:tt )*) => {
+        $crate::__nan_free_options!(@boundary $args $flag [$( $done )* ;] $( $rest )*);
+    };
+    (@inside $args:tt $flag:tt [$( $done:tt )*] $next:tt $( $rest:tt )*) => {
+        $crate::__nan_free_options!(@inside $args $flag [$( $done )* $next] $( $rest )*);
+    };
+    (@boundary $args:tt $flag:tt $done:tt total_order $( ; $( $rest:tt )* )?) => {
+        $crate::__nan_free_options!(@boundary $args [total_order] $done $( $( $rest )* )?);
+    };
+    (@boundary $args:tt $flag:tt $done:tt $( $rest:tt )*) => {
+        $crate::__nan_free_options!(@inside $args $flag $done $( $rest )*);
+    };
+    (@total_order [$( $args:tt )*]) => {
+        $crate::add_total_order!($( $args )*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_hash {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::hash::Hash for $type
+        where
+            $parent: core::hash::Hash,
+            $( $where )*
+        {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                <$parent as core::hash::Hash>::hash(&self.0, state);
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_ord {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::PartialOrd for $type
+        where
+            $parent: core::cmp::Ord,
+            Self: core::cmp::Eq,
+            $( $where )*
+        {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        #[automatically_derived]
+        impl<$( $gen )*> core::cmp::Ord for $type
+        where
+            $parent: core::cmp::Ord,
+            Self: core::cmp::Eq,
+            $( $where )*
+        {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                <$parent as core::cmp::Ord>::cmp(&self.0, &other.0)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_default {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $value:expr) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::default::Default for $type
+        where
+            $( $where )*
+        {
+            fn default() -> Self {
+                let val: $parent = $value;
+                match <Self as core::convert::TryFrom<$parent>>::try_from(val) {
+                    Ok(val) => val,
+                    Err(_) => panic!(concat!("default value of `", stringify!($type), "` doesn't satisfy the predicate")),
+                }
+            }
+        }
+
+        $crate::add_deserialize_or_default!([$( $gen )*] [$( $where )*] $type, $parent);
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize_or_default {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        impl<$( $gen )*> $type
+        where
+            $( $where )*
+        {
+            /// Deserializes the newtype, falling back to the default if the value is invalid.
+            /// Intended for `#[serde(deserialize_with = "...")]`.
+            #[allow(dead_code)]
+            pub fn deserialize_or_default<'de, D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                $parent: serde::Deserialize<'de>,
+            {
+                let val = <$parent as serde::Deserialize<'de>>::deserialize(deserializer)?;
+                Ok(<Self as core::convert::TryFrom<$parent>>::try_from(val).unwrap_or_default())
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_deserialize_or_default {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_borrow {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::borrow::Borrow<$parent> for $type
+        where
+            $( $where )*
+        {
+            fn borrow(&self) -> &$parent {
+                &self.0
+            }
+        }
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $target:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> core::borrow::Borrow<$target> for $type
+        where
+            $parent: core::borrow::Borrow<$target>,
+            $( $where )*
+        {
+            fn borrow(&self) -> &$target {
+                <$parent as core::borrow::Borrow<$target>>::borrow(&self.0)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_proptest {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [in, $min:literal ..= $max:literal]) => {
+        $crate::add_proptest!(@range [$( $gen )*] [$( $where )*] $type, $parent, core::ops::RangeInclusive<$parent>, $min..=$max);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [in, ..= $max:literal]) => {
+        $crate::add_proptest!(@range [$( $gen )*] [$( $where )*] $type, $parent, core::ops::RangeToInclusive<$parent>, ..=$max);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [in, $min:literal .. $max:literal]) => {
+        $crate::add_proptest!(@range [$( $gen )*] [$( $where )*] $type, $parent, core::ops::Range<$parent>, $min..$max);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [in, $min:literal ..]) => {
+        $crate::add_proptest!(@range [$( $gen )*] [$( $where )*] $type, $parent, core::ops::RangeFrom<$parent>, $min..);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, [in, .. $max:literal]) => {
+        $crate::add_proptest!(@range [$( $gen )*] [$( $where )*] $type, $parent, core::ops::RangeTo<$parent>, ..$max);
+    };
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $bounds:tt) => {
+        #[automatically_derived]
+        impl<$( $gen )*> proptest::arbitrary::Arbitrary for $type
+        where
+            $parent: proptest::arbitrary::Arbitrary,
+            Self: core::fmt::Debug,
+            $( $where )*
+        {
+            type Parameters = <$parent as proptest::arbitrary::Arbitrary>::Parameters;
+            type Strategy = proptest::strategy::FilterMap<
+                <$parent as proptest::arbitrary::Arbitrary>::Strategy,
+                fn($parent) -> Option<Self>,
+            >;
+
+            fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+                let try_from: fn($parent) -> Option<Self> =
+                    |val| <Self as core::convert::TryFrom<$parent>>::try_from(val).ok();
+                proptest::strategy::Strategy::prop_filter_map(
+                    <$parent as proptest::arbitrary::Arbitrary>::arbitrary_with(args),
+                    concat!("value doesn't satisfy ", stringify!($type), " predicate"),
+                    try_from,
+                )
+            }
+        }
+    };
+    (@range [$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $range_type:ty, $range:expr) => {
+        #[automatically_derived]
+        impl<$( $gen )*> proptest::arbitrary::Arbitrary for $type
+        where
+            $( $where )*
+        {
+            type Parameters = ();
+            type Strategy = proptest::strategy::Map<$range_type, fn($parent) -> Self>;
+
+            fn arbitrary_with(_args: ()) -> Self::Strategy {
+                proptest::strategy::Strategy::prop_map($range, Self as fn($parent) -> Self)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "proptest"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_proptest {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty, $bounds:tt) => {
+        compile_error!("`proptest` option requires `proptest` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "quickcheck")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_quickcheck {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<$( $gen )*> quickcheck::Arbitrary for $type
+        where
+            $parent: quickcheck::Arbitrary,
+            Self: Clone,
+            $( $where )*
+        {
+            fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+                for _ in 0..10_000 {
+                    let val = <$parent as quickcheck::Arbitrary>::arbitrary(g);
+                    if let Ok(val) = <Self as core::convert::TryFrom<$parent>>::try_from(val) {
+                        return val;
+                    }
+                }
+                panic!(concat!(
+                    "failed to generate ",
+                    stringify!($type),
+                    " in 10000 attempts, predicate is too restrictive"
+                ));
+            }
+
+            fn shrink(&self) -> $crate::__private::Box<dyn Iterator<Item = Self>> {
+                $crate::__private::Box::new(
+                    <$parent as quickcheck::Arbitrary>::shrink(&self.0)
+                        .filter_map(|val| <Self as core::convert::TryFrom<$parent>>::try_from(val).ok()),
+                )
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "quickcheck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_quickcheck {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        compile_error!("`quickcheck` option requires `quickcheck` feature of validated_newtype");
+    };
+}
+
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_arbitrary {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        #[automatically_derived]
+        impl<'a, $( $gen )*> arbitrary::Arbitrary<'a> for $type
+        where
+            $parent: arbitrary::Arbitrary<'a>,
+            $( $where )*
+        {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                let val = <$parent as arbitrary::Arbitrary<'a>>::arbitrary(u)?;
+                <Self as core::convert::TryFrom<$parent>>::try_from(val)
+                    .map_err(|_| arbitrary::Error::IncorrectFormat)
+            }
+
+            fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                let val = <$parent as arbitrary::Arbitrary<'a>>::arbitrary_take_rest(u)?;
+                <Self as core::convert::TryFrom<$parent>>::try_from(val)
+                    .map_err(|_| arbitrary::Error::IncorrectFormat)
+            }
+
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <$parent as arbitrary::Arbitrary<'a>>::size_hint(depth)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "arbitrary"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_arbitrary {
+    ([$( $gen:tt )*] [$( $where:tt )*] $type:ty, $parent:ty) => {
+        compile_error!("`arbitrary` option requires `arbitrary` feature of validated_newtype");
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! add_options {
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt;) => {};
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; from_str $(; $($rest:tt)*)?) => {
+        $crate::add_from_str!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; from [ $( $source:tt )* ] $(; $($rest:tt)*)?) => {
+        $crate::add_try_from_sources!($generics $where_clause $type, $parent; $( $source )*);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; from_newtype [ $( $source:tt )* ] $(; $($rest:tt)*)?) => {
+        $crate::add_try_from_newtypes!($generics $where_clause $type, $parent; $( $source )*);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; try_from_ref $(; $($rest:tt)*)?) => {
+        $crate::add_try_from_ref!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; modify $(; $($rest:tt)*)?) => {
+        $crate::add_modify!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; display $(; $($rest:tt)*)?) => {
+        $crate::add_display!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; partial_eq $(; $($rest:tt)*)?) => {
+        $crate::add_partial_eq_parent!(@unless_ord $generics $where_clause $type, $parent; $($($rest)*)?);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; partial_ord $(; $($rest:tt)*)?) => {
+        $crate::add_partial_ord_parent!(@scan $generics $where_clause $type, $parent; $($($rest)*)?);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; hash $(; $($rest:tt)*)?) => {
+        $crate::add_hash!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; ord $(; $($rest:tt)*)?) => {
+        $crate::add_ord!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; total_order $(; $($rest:tt)*)?) => {
+        compile_error!("`total_order` option requires `finite` or `non_nan` predicate, since NaN is incomparable");
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; default $value:expr $(; $($rest:tt)*)?) => {
+        $crate::add_default!($generics $where_clause $type, $parent, $value);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; as_ref $target:ty $(; $($rest:tt)*)?) => {
+        $crate::add_as_ref!($generics $where_clause $type, $parent, $target);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; borrow $(; $($rest:tt)*)?) => {
+        $crate::add_borrow!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; borrow $target:ty $(; $($rest:tt)*)?) => {
+        $crate::add_borrow!($generics $where_clause $type, $parent, $target);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; proptest $(; $($rest:tt)*)?) => {
+        $crate::add_proptest!($generics $where_clause $type, $parent, $bounds);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; quickcheck $(; $($rest:tt)*)?) => {
+        $crate::add_quickcheck!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; arbitrary $(; $($rest:tt)*)?) => {
+        $crate::add_arbitrary!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; transparent_repr $(; $($rest:tt)*)?) => {
+        // Handled by `uniform_validated_newtype!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; json_schema $(; $($rest:tt)*)?) => {
+        $crate::add_json_schema!($generics $where_clause $type, $parent, $bounds);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; borsh $(; $($rest:tt)*)?) => {
+        $crate::add_borsh!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; validator $(; $($rest:tt)*)?) => {
+        // `validate_custom()` is generated by `add_validator!` when predicate is given
+        $crate::add_validator!($generics $where_clause $type);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; miniserde $(; $($rest:tt)*)?) => {
+        $crate::add_miniserde!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; bytemuck $(; $($rest:tt)*)?) => {
+        $crate::add_bytemuck!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; sqlx $(; $($rest:tt)*)?) => {
+        $crate::add_sqlx!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; diesel $sql_type:ty $(; $($rest:tt)*)?) => {
+        $crate::add_diesel!($generics $where_clause $type, $parent, $sql_type);
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; invalid_value $expected:literal $(; $($rest:tt)*)?) => {
+        // Handled by `add_deserialize!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; deserialize_borrowed $(; $($rest:tt)*)?) => {
+        // Handled by `add_deserialize!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; expecting $( $description:literal )? $(; $($rest:tt)*)?) => {
+        // Handled by `add_deserialize!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; sanitize $sanitize:expr $(; $($rest:tt)*)?) => {
+        // Handled by `add_try_from!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; default_context $(; $($rest:tt)*)?) => {
+        // Handled by `uniform_validated_newtype!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; prefix_errors $(; $($rest:tt)*)?) => {
+        // Handled by `add_deserialize!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; deref $target:ty $(; $($rest:tt)*)?) => {
+        // Handled by `add_deref!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; no_deref $(; $($rest:tt)*)?) => {
+        // Handled by `add_deref!`
+        $crate::add_options!($generics $where_clause $type, $parent, $bounds; $($($rest)*)?);
+    };
+    ($generics:tt $where_clause:tt $type:ty, $parent:ty, $bounds:tt; $option:tt $($rest:tt)*) => {
+        compile_error!(concat!("unknown validated_newtype option: ", stringify!($option)));
     };
-}
-
-#[cfg(not(feature = "serde"))]
-#[doc(hidden)]
-#[macro_export]
-macro_rules! add_deserialize {
-    ($type:ident, $parent:ty) => {};
 }
 
 #[doc(hidden)]
 #[macro_export]
-macro_rules! add_try_from {
-    ($type:ident, $parent:ty, $predicate:expr, $error_type:ty, $error:expr) => {
-        impl core::convert::TryFrom<$parent> for $type {
-            type Error = $error_type;
-
-            fn try_from(val: $parent) -> Result<Self, $error_type> {
-                if $predicate(&val) {
-                    Ok($type(val))
-                } else {
-                    Err($error(&val).into())
-                }
-            }
+macro_rules! uniform_validated_newtype {
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ]
+        $( $rest:tt )*
+    ) => {
+        $crate::uniform_validated_newtype! {
+            @expand
+            $( #[$attr] )*
+            $parent => $vis $type [ $( $gen )* ] [ $( $where )* ] [$( $( $field )* )?] $type<$( $type_arg )*>;
+            $( $rest )*
         }
     };
-}
-
-#[doc(hidden)]
-#[macro_export]
-macro_rules! add_deref {
-    ($type:ident, $parent:ty) => {
-        impl core::ops::Deref for $type {
-            type Target = $parent;
-
-            fn deref(&self) -> &$parent {
-                &self.0
-            }
+    (
+        @expand
+        $( #[$attr:meta] )*
+        $parent:ty => $vis:vis $name:ident $generics:tt $where_clause:tt [$( $field:tt )*] $type:ty;
+        context check $check:expr => $error_type:ty;
+        [ $( $option:tt )* ]
+    ) => {
+        $crate::uniform_validated_newtype! {
+            @context
+            [ @expand $( #[$attr] )* $parent => $vis $name $generics $where_clause [$( $field )*] $type; ]
+            [ check $check => $error_type; ]
+            [ $( $option )* ];
+            $( $option )*
         }
     };
-}
-
-#[doc(hidden)]
-#[macro_export]
-macro_rules! uniform_validated_newtype {
+    // Predicate taking a context can only be checked without it with `default_context` option
+    (@context [$( $head:tt )*] $check:tt $options:tt;) => {
+        $crate::uniform_validated_newtype!($( $head )* context; $options);
+    };
+    (@context [$( $head:tt )*] [$( $check:tt )*] $options:tt; default_context $( $rest:tt )*) => {
+        $crate::uniform_validated_newtype!($( $head )* $( $check )* $options);
+    };
+    (@context $head:tt $check:tt $options:tt; $skip:tt $( $rest:tt )*) => {
+        $crate::uniform_validated_newtype!(@context $head $check $options; $( $rest )*);
+    };
     (
+        @expand
         $( #[$attr:meta] )*
-        $parent:ty => $vis:vis $type:ident
+        $parent:ty => $vis:vis $name:ident $generics:tt $where_clause:tt [$( $field:tt )*] $type:ty;
+        context;
+        [ $( $option:tt )* ]
+    ) => {
+        $crate::uniform_validated_newtype!(
+            @repr [$( #[$attr] )*] [$vis $name $generics $where_clause [$( $field )*] ($parent)];
+            $( $option )*
+        );
+        $crate::add_serialize!($generics $where_clause $type, $parent);
+        $crate::add_deref!($generics $where_clause $type, $parent; $( $option )*);
+        $crate::add_as_ref!($generics $where_clause $type, $parent);
+        $crate::add_into_inner!($generics $where_clause $type, $parent);
+        $crate::add_into_parent!($generics $where_clause $type, $parent);
+        $crate::add_new_unchecked!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, []; $( $option )*);
+    };
+    (
+        @expand
+        $( #[$attr:meta] )*
+        $parent:ty => $vis:vis $name:ident $generics:tt $where_clause:tt [$( $field:tt )*] $type:ty;
+        $(
+            check $check:expr => $error_type:ty;
+        )?
         $(
-            if $predicate:expr;
-            else $error:expr => $error_type:ty
+            bounds [ $( $bounds:tt )* ];
         )?
+        [ $( $option:tt )* ]
     ) => {
-        #[allow(unused_attributes)]
-        $( #[$attr] )*
-        $vis struct $type($parent);
+        $crate::uniform_validated_newtype!(
+            @repr [$( #[$attr] )*] [$vis $name $generics $where_clause [$( $field )*] ($parent)];
+            $( $option )*
+        );
+        $crate::uniform_validated_newtype! {
+            @impls
+            $parent => $generics $where_clause $type;
+            $( check $check => $error_type; )?
+            $( bounds [ $( $bounds )* ]; )?
+            [ $( $option )* ]
+        }
+    };
+    (
+        @impls
+        $parent:ty => $generics:tt $where_clause:tt $type:ty;
         $(
-            $crate::add_try_from!($type, $parent, $predicate, $error_type, $error);
+            check $check:expr => $error_type:ty;
         )?
-        $crate::add_deserialize!($type, $parent);
-        $crate::add_deref!($type, $parent);
-    }
+        $(
+            bounds [ $( $bounds:tt )* ];
+        )?
+        [ $( $option:tt )* ]
+    ) => {
+        $crate::uniform_validated_newtype! {
+            @sanitize
+            [
+                $parent => $generics $where_clause $type;
+                $( check $check => $error_type; )?
+                $( bounds [ $( $bounds )* ]; )?
+                [ $( $option )* ]
+            ];
+            $( $option )*
+        }
+    };
+    (@sanitize [$( $head:tt )*];) => {
+        $crate::uniform_validated_newtype!(@emit []; $( $head )*);
+    };
+    (@sanitize [$( $head:tt )*]; sanitize $sanitize:expr $(; $( $rest:tt )*)?) => {
+        $crate::uniform_validated_newtype!(@emit [$sanitize]; $( $head )*);
+    };
+    (@sanitize $head:tt; $skip:tt $( $rest:tt )*) => {
+        $crate::uniform_validated_newtype!(@sanitize $head; $( $rest )*);
+    };
+    (
+        @emit $sanitize:tt;
+        $parent:ty => $generics:tt $where_clause:tt $type:ty;
+        $(
+            check $check:expr => $error_type:ty;
+        )?
+        $(
+            bounds [ $( $bounds:tt )* ];
+        )?
+        [ $( $option:tt )* ]
+    ) => {
+        $(
+            $crate::add_validate!($generics $where_clause $type, $parent, $check, $error_type, $sanitize);
+            $crate::add_try_from!($generics $where_clause $type, $parent, $check, $error_type, $sanitize);
+            $crate::add_new!($generics $where_clause $type, $parent, $error_type);
+            $crate::add_map_checked!($generics $where_clause $type, $parent, $error_type);
+        )?
+        $crate::add_validator!(@custom $generics $where_clause $type, $parent, [$( $check )?]; $( $option )*);
+        $crate::add_deserialize!($generics $where_clause $type, $parent, [$( $check )?], $sanitize; $( $option )*);
+        $crate::add_serialize!($generics $where_clause $type, $parent);
+        $crate::add_deserialize_checked!($generics $where_clause $type, $parent);
+        $crate::add_deserialize_vec_checked!($generics $where_clause $type, $parent);
+        $crate::add_deref!($generics $where_clause $type, $parent; $( $option )*);
+        $crate::add_as_ref!($generics $where_clause $type, $parent);
+        $crate::add_into_inner!($generics $where_clause $type, $parent);
+        $crate::add_into_parent!($generics $where_clause $type, $parent);
+        $crate::add_new_unchecked!($generics $where_clause $type, $parent);
+        $crate::add_options!($generics $where_clause $type, $parent, [$( $( $bounds )* )?]; $( $option )*);
+    };
+    (@repr [$( $attr:tt )*] [$( $struct:tt )*];) => {
+        $crate::uniform_validated_newtype!(@struct $( $attr )* $( $struct )*);
+    };
+    (@repr [$( $attr:tt )*] [$( $struct:tt )*]; transparent_repr $( $rest:tt )*) => {
+        $crate::uniform_validated_newtype!(@struct $( $attr )* #[repr(transparent)] $( $struct )*);
+    };
+    (@repr [$( $attr:tt )*] [$( $struct:tt )*]; bytemuck $( $rest:tt )*) => {
+        $crate::uniform_validated_newtype!(@struct $( $attr )* #[repr(transparent)] $( $struct )*);
+    };
+    (@repr $attrs:tt $struct:tt; $skip:tt $( $rest:tt )*) => {
+        $crate::uniform_validated_newtype!(@repr $attrs $struct; $( $rest )*);
+    };
+    (
+        @struct $( #[$attr:meta] )* $vis:vis $name:ident []
+        [$( $where:tt )*] [$( $field:tt )*] ($parent:ty)
+    ) => {
+        #[allow(unused_attributes)]
+        $( #[$attr] )*
+        $vis struct $name($( $field )* $parent)
+        where
+            $( $where )*;
+    };
+    (
+        @struct $( #[$attr:meta] )* $vis:vis $name:ident [ $( $gen:tt )* ]
+        [$( $where:tt )*] [$( $field:tt )*] ($parent:ty)
+    ) => {
+        #[allow(unused_attributes)]
+        $( #[$attr] )*
+        $vis struct $name<$( $gen )*>($( $field )* $parent)
+        where
+            $( $where )*;
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident
+        $( $rest:tt )*
+    ) => {
+        $crate::uniform_validated_newtype! {
+            @expand
+            $( #[$attr] )*
+            $parent => $vis $type [] [] [$( $( $field )* )?] $type;
+            $( $rest )*
+        }
+    };
 }
 
 /// Macro to create deserializable newtype with predicate validation.
 /// See crate docs for examples.
 #[macro_export]
 macro_rules! validated_newtype {
+    ($( { $( $definition:tt )* } )+) => {
+        $( $crate::validated_newtype! { $( $definition )* } )+
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        transparent
+        $( $rest:tt )*
+    ) => {
+        // `transparent` marker doesn't change the generated code
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            $( $rest )*
+        }
+    };
     (
         $( #[$attr:meta] )*
         $parent:ty => $vis:vis $type:ident
-        $(
-            if $predicate:expr;
-            else $error:expr => $error_type:ty
-        )?
+        < $( $lifetime:lifetime ),+
+        $( , $gen:ident $( : $( $bound:ident )::+ $( + $( $more:ident )::+ )* )? )* $(,)? >
+        $( $rest:tt )*
     ) => {
-        $crate::uniform_validated_newtype! {
+        $crate::validated_newtype! {
             $( #[$attr] )*
             $parent => $vis $type
-            $(
-                if $predicate;
-                else $error => $error_type
-            )?
+            [ $( $lifetime ),+ $( , $gen $( : $( $bound )::+ $( + $( $more )::+ )* )? )* ]
+            [ $( $lifetime ),+ $( , $gen )* ] []
+            $( $rest )*
         }
     };
     (
         $( #[$attr:meta] )*
         $parent:ty => $vis:vis $type:ident
-        if $predicate:expr;
-        error $message:literal
+        < $( $gen:ident $( : $( $bound:ident )::+ $( + $( $more:ident )::+ )* )? ),* $(,)? >
+        $( $rest:tt )*
     ) => {
-        $crate::uniform_validated_newtype! {
+        $crate::validated_newtype! {
             $( #[$attr] )*
             $parent => $vis $type
+            [ $( $gen $( : $( $bound )::+ $( + $( $more )::+ )* )? ),* ] [ $( $gen ),* ] []
+            $( $rest )*
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        ( $( #[$field_attr:meta] )* $field_vis:vis _ )
+        $( $rest:tt )*
+    ) => {
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            @field($( #[$field_attr] )* $field_vis)
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            $( $rest )*
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        ( $( #[$field_attr:meta] )* $field_vis:vis $field_type:ty )
+        $( $rest:tt )*
+    ) => {
+        const _: () = {
+            #[allow(dead_code)]
+            fn field_type_must_match_base_type<$( $( $gen )* )?>(val: $parent) -> $field_type {
+                val
+            }
+        };
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            @field($( #[$field_attr] )* $field_vis)
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            $( $rest )*
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [] )?
+        where $( $rest:tt )*
+    ) => {
+        $crate::validated_newtype! {
+            @where
+            [ $( #[$attr] )* $( @field($( $field )*) )? $parent => $vis $type ]
+            [ $( $( $gen )* )? ] [ $( $( $type_arg )* )? ] []
+            $( $rest )*
+        }
+    };
+    (@where [ $( $head:tt )* ] $gen:tt $type_arg:tt $where:tt ; $( $rest:tt )*) => {
+        $crate::validated_newtype! {
+            $( $head )* $gen $type_arg $where
+            $( $rest )*
+        }
+    };
+    (@where $head:tt $gen:tt $type_arg:tt [ $( $where:tt )* ] $next:tt $( $rest:tt )*) => {
+        $crate::validated_newtype! {
+            @where $head $gen $type_arg [ $( $where )* $next ]
+            $( $rest )*
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if const $predicate:path;
+        $( $rest:tt )*
+    ) => {
+        $crate::add_new_const!(
+            [$( $( $gen )* )?] [$( $( $where )* )?] $type $( <$( $type_arg )*> )?,
+            $parent,
+            $predicate
+        );
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
             if $predicate;
+            $( $rest )*
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if |$arg:ident: &$arg_type:ty, $ctx:ident: &$ctx_type:ty| $body:expr;
+        else $error:expr => $error_type:ty
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_new_with!(
+            [$( $( $gen )* )?] [$( $( $where )* )?] $type $( <$( $type_arg )*> )?,
+            $parent,
+            $ctx_type,
+            |$arg: &$arg_type, $ctx: &$ctx_type| -> bool { $body },
+            $error => $error_type
+        );
+        $crate::uniform_validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            context check |val: &$parent| -> Result<(), $error_type> {
+                Self::validate_with(val, &<$ctx_type as core::default::Default>::default())
+            } => $error_type;
+            [ $( $( $option )* )? ]
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if |$arg:ident: &$arg_type:ty, $ctx:ident: &$ctx_type:ty| $body:expr;
+        error $message:literal
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if |$arg: &$arg_type, $ctx: &$ctx_type| $body;
+            else |_| $message => &'static str
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if |$arg:ident: &$arg_type:ty, $ctx:ident: &$ctx_type:ty| $body:expr;
+        error type $error_name:ident = $message:literal
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_error_type!($vis $error_name, $type, $message);
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if |$arg: &$arg_type, $ctx: &$ctx_type| $body;
+            else |_| $error_name => $error_name
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if |$arg:ident: &$arg_type:ty, $ctx:ident: &$ctx_type:ty| $body:expr;
+        error $error_name:ident $( $message:literal )?
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_error_type!($vis $error_name, $type, $parent $(, $message )?);
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if |$arg: &$arg_type, $ctx: &$ctx_type| $body;
+            else |val: &$parent| $error_name { value: core::clone::Clone::clone(val) } => $error_name
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if $( $predicate:expr ),+ $(,)?;
+        else $error:expr => $error_type:ty
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::uniform_validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            check |val: &$parent| -> Result<(), $error_type> {
+                if $( $predicate(val) )&&+ {
+                    Ok(())
+                } else {
+                    Err($error(val).into())
+                }
+            } => $error_type;
+            [ $( $( $option )* )? ]
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if $( $predicate:expr ),+ $(,)?;
+        error $message:literal
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if $( $predicate ),+;
             else |_| $message => &'static str
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if $( $predicate:expr ),+ $(,)?;
+        error type $error_name:ident = $message:literal
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_error_type!($vis $error_name, $type, $message);
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if $( $predicate ),+;
+            else |_| $error_name => $error_name
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if $( $predicate:expr ),+ $(,)?;
+        error $error_name:ident $( $message:literal )?
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_error_type!($vis $error_name, $type, $parent $(, $message )?);
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if $( $predicate ),+;
+            else |val: &$parent| $error_name { value: core::clone::Clone::clone(val) } => $error_name
+            $( ; $( $option )* )?
         }
     };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        if $( $predicate:expr => $message:literal ),+ $(,)?;
+        $( $option:tt )*
+    ) => {
+        $crate::uniform_validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            check |val: &$parent| -> Result<(), &'static str> {
+                $(
+                    if !$predicate(val) {
+                        return Err($message);
+                    }
+                )+
+                Ok(())
+            } => &'static str;
+            [ $( $option )* ]
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        $measure:ident $( $min:literal )? ..= $max:literal
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_range_bounds!(
+            $measure,
+            [$( $( $gen )* )?] [$( $( $where )* )?] $type $( <$( $type_arg )*> )?,
+            $parent,
+            [$( $min )?],
+            [$max]
+        );
+        $crate::uniform_validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            check |val: &$parent| -> Result<(), &'static str> {
+                if $crate::in_range!($crate::range_measure!($measure, val), $( $min )? ..= $max) {
+                    Ok(())
+                } else {
+                    Err($crate::range_message!($measure, $( $min )? ..= $max))
+                }
+            } => &'static str;
+            bounds [$measure, $( $min )? ..= $max];
+            [ $( $( $option )* )? ]
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        $measure:ident $( $min:literal )? .. $( $max:literal )?
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_range_bounds!(
+            $measure,
+            [$( $( $gen )* )?] [$( $( $where )* )?] $type $( <$( $type_arg )*> )?,
+            $parent,
+            [$( $min )?],
+            []
+        );
+        $crate::uniform_validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            check |val: &$parent| -> Result<(), &'static str> {
+                if $crate::in_range!($crate::range_measure!($measure, val), $( $min )? .. $( $max )?) {
+                    Ok(())
+                } else {
+                    Err($crate::range_message!($measure, $( $min )? .. $( $max )?))
+                }
+            } => &'static str;
+            bounds [$measure, $( $min )? .. $( $max )?];
+            [ $( $( $option )* )? ]
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        non_empty
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if |val: &$parent| !val.is_empty();
+            error "value must not be empty"
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        finite
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::__nan_free_options! {
+            @inside
+            [[$( $( $gen )* )?] [$( $( $where )* )?] $type $( <$( $type_arg )*> )?, $parent]
+            []
+            [
+                $( #[$attr] )*
+                $( @field($( $field )*) )?
+                $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+                if |val: &$parent| val.is_finite();
+                error "value must be finite"
+            ]
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        non_nan
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::__nan_free_options! {
+            @inside
+            [[$( $( $gen )* )?] [$( $( $where )* )?] $type $( <$( $type_arg )*> )?, $parent]
+            []
+            [
+                $( #[$attr] )*
+                $( @field($( $field )*) )?
+                $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+                if |val: &$parent| !val.is_nan();
+                error "value must not be NaN"
+            ]
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        matches $pattern:literal
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::add_regex!(
+            [$( $( $gen )* )?] [$( $( $where )* )?] $type $( <$( $type_arg )*> )?,
+            $pattern
+        );
+        $crate::validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            if $crate::regex_predicate!($parent, $pattern);
+            else |_| concat!("value must match pattern ", $pattern) => &'static str
+            $( ; $( $option )* )?
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        validate |$arg:ident $( : $arg_type:ty )?| -> Result<(), $error_type:ty> $body:block
+        $( ; $( $option:tt )* )?
+    ) => {
+        $crate::uniform_validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            check |$arg: &$parent| -> Result<(), $error_type> {
+                $( let $arg: $arg_type = $arg; )?
+                $body
+            } => $error_type;
+            [ $( $( $option )* )? ]
+        }
+    };
+    (
+        $( #[$attr:meta] )*
+        $( @field($( $field:tt )*) )?
+        $parent:ty => $vis:vis $type:ident $( [ $( $gen:tt )* ] [ $( $type_arg:tt )* ] [ $( $where:tt )* ] )?
+        $( $option:tt )*
+    ) => {
+        $crate::uniform_validated_newtype! {
+            $( #[$attr] )*
+            $( @field($( $field )*) )?
+            $parent => $vis $type $( [ $( $gen )* ] [ $( $type_arg )* ] [ $( $where )* ] )?
+            [ $( $option )* ]
+        }
+    };
+}
+
+/// Constructs a newtype with `if const` predicate in a constant, so invalid values are rejected
+/// at compile time. See crate docs for examples.
+#[macro_export]
+macro_rules! valid {
+    ($type:ty, $value:expr $(,)?) => {{
+        const VALUE: $type = match <$type>::new_const($value) {
+            Some(val) => val,
+            None => panic!(concat!("invalid value for `", stringify!($type), "`: ", stringify!($value))),
+        };
+        VALUE
+    }};
 }
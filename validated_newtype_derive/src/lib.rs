@@ -0,0 +1,208 @@
+//! Derive macro front-end for [validated_newtype](https://docs.rs/validated_newtype).
+//! Use it via the `derive` feature of `validated_newtype` instead of depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned as _, Data, DeriveInput, Expr, Fields, GenericParam, LitStr,
+};
+
+#[derive(Default)]
+struct Config {
+    predicates: Vec<Expr>,
+    error: Option<LitStr>,
+    options: TokenStream2,
+}
+
+impl Config {
+    fn from_attrs(input: &DeriveInput) -> syn::Result<Self> {
+        let mut config = Self::default();
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("validated"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("predicate") {
+                    config.predicates.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("error") {
+                    if config.error.is_some() {
+                        return Err(meta.error("duplicate `error`"));
+                    }
+                    config.error = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("options") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let options: TokenStream2 = content.parse()?;
+                    config.options.extend(options);
+                } else {
+                    return Err(meta.error(
+                        "unknown validated attribute, expected `predicate`, `error` or `options`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        match (config.predicates.first(), &config.error) {
+            (Some(predicate), None) => Err(syn::Error::new(
+                predicate.span(),
+                "`predicate` requires an `error` message",
+            )),
+            (None, Some(error)) => Err(syn::Error::new(
+                error.span(),
+                "`error` requires a `predicate`",
+            )),
+            _ => Ok(config),
+        }
+    }
+}
+
+/// Rejects options that rely on the struct layout, since a derive can't change the definition.
+fn check_layout_options(input: &DeriveInput, options: &TokenStream2) -> syn::Result<()> {
+    let is_transparent = input.attrs.iter().any(|attr| {
+        attr.path().is_ident("repr")
+            && attr.meta.require_list().is_ok_and(|list| {
+                list.tokens
+                    .clone()
+                    .into_iter()
+                    .any(|token| matches!(token, TokenTree::Ident(ident) if ident == "transparent"))
+            })
+    });
+
+    let mut option_start = true;
+    for token in options.clone() {
+        if let (true, TokenTree::Ident(ident)) = (option_start, &token) {
+            if ident == "transparent_repr" {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "`transparent_repr` can't change a derived struct, add `#[repr(transparent)]` instead",
+                ));
+            }
+            if ident == "bytemuck" && !is_transparent {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "`bytemuck` option requires `#[repr(transparent)]` on the struct",
+                ));
+            }
+        }
+        option_start = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ';');
+    }
+    Ok(())
+}
+
+/// Generates the same impls as `validated_newtype!` for a user-written tuple struct.
+///
+/// ```
+/// use validated_newtype::Validated;
+///
+/// #[derive(Debug, Validated)]
+/// #[validated(predicate = |n: &u32| *n <= 100, error = "percent must be in range 0-100")]
+/// #[validated(options(display; partial_ord))]
+/// pub struct Percent(u32);
+///
+/// assert_eq!(*Percent::new(42).unwrap(), 42);
+/// assert_eq!(Percent::new(142).unwrap_err(), "percent must be in range 0-100");
+/// assert_eq!(Percent::new(42).unwrap().to_string(), "42");
+/// assert!(Percent::new(42).unwrap() > 10);
+/// ```
+///
+/// Options that rely on the layout need `#[repr(transparent)]` written on the struct:
+/// ```
+/// use bytemuck::TransparentWrapper;
+/// use validated_newtype::Validated;
+///
+/// #[derive(Debug, Validated)]
+/// #[validated(predicate = |n: &u32| *n <= 100, error = "percent must be in range 0-100")]
+/// #[validated(options(bytemuck))]
+/// #[repr(transparent)]
+/// pub struct Percent(u32);
+///
+/// assert_eq!(*Percent::peel_ref(&Percent::new(42).unwrap()), 42);
+/// ```
+///
+/// Without it, `bytemuck` option is rejected:
+/// ```compile_fail
+/// use validated_newtype::Validated;
+///
+/// #[derive(Debug, Validated)]
+/// #[validated(predicate = |n: &u32| *n <= 100, error = "percent must be in range 0-100")]
+/// #[validated(options(bytemuck))]
+/// pub struct Percent(u32);
+/// ```
+#[proc_macro_derive(Validated, attributes(validated))]
+pub fn derive_validated(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let parent = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+            fields => {
+                return Err(syn::Error::new(
+                    fields.span(),
+                    "`Validated` requires a tuple struct with exactly one field",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`Validated` can only be derived for structs",
+            ))
+        }
+    };
+    let config = Config::from_attrs(&input)?;
+    check_layout_options(&input, &config.options)?;
+
+    let name = &input.ident;
+    let (_, type_generics, where_clause) = input.generics.split_for_impl();
+    // Impl generics without angle brackets, since helper macros add their own parameters
+    let params = input.generics.params.iter().map(|param| {
+        let mut param = param.clone();
+        match &mut param {
+            GenericParam::Type(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+        param
+    });
+    let bounds = where_clause
+        .map(|clause| clause.predicates.iter())
+        .into_iter()
+        .flatten();
+
+    let predicates = &config.predicates;
+    let check = config.error.map(|error| {
+        quote! {
+            check |val: &#parent| -> Result<(), &'static str> {
+                if #( (#predicates)(val) )&&* {
+                    Ok(())
+                } else {
+                    Err(#error)
+                }
+            } => &'static str;
+        }
+    });
+    let options = config.options;
+
+    Ok(quote! {
+        ::validated_newtype::uniform_validated_newtype! {
+            @impls
+            #parent => [#( #params ),*] [#( #bounds, )*] #name #type_generics;
+            #check
+            [#options]
+        }
+    })
+}